@@ -1,52 +1,46 @@
 use bevy::{
-    app::{App, Update},
+    app::{App, FixedUpdate},
     ecs::{schedule::IntoScheduleConfigs, system::Res},
-    prelude::{AppExtStates, Commands, OnEnter, Query, Resource, States, in_state},
+    prelude::{AppExtStates, Commands, OnEnter, Resource, States, Time, in_state},
     state::app::StatesPlugin,
 };
 use bevy_godot4::prelude::{
-    AsPhysicsSystem, ErasedGd, ErasedGdResource, GodotScene, SystemDeltaTimer, bevy_app,
-};
-use godot::{
-    builtin::Vector2,
-    classes::{ResourceLoader, Sprite2D},
+    ErasedGdResource, GodotDefaultPlugins, GodotNodeAppExt, GodotNodeQuery,
+    GodotResourceCollection, GodotResourceLoadingPlugin, GodotScene, bevy_app,
 };
+use godot::{builtin::Vector2, classes::Sprite2D};
 use godot::{init::ExtensionLibrary, prelude::gdextension};
 
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash, States)]
 enum GameState {
     #[default]
+    Loading,
     Playing,
 }
 
 #[bevy_app]
 fn build_app(app: &mut App) {
-    app.add_plugins(StatesPlugin)
+    app.add_plugins(GodotDefaultPlugins)
+        .add_plugins(StatesPlugin)
         .init_state::<GameState>()
-        .init_resource::<MyAssets>()
+        .add_plugins(
+            GodotResourceLoadingPlugin::new(GameState::Loading, GameState::Playing)
+                .load_collection::<MyAssets>(),
+        )
+        .register_godot_node::<Sprite2D>()
         .add_systems(OnEnter(GameState::Playing), spawn_sprite)
         .add_systems(
-            Update,
-            move_sprite
-                .as_physics_system()
-                .run_if(in_state(GameState::Playing)),
+            FixedUpdate,
+            move_sprite.run_if(in_state(GameState::Playing)),
         );
 }
 
-#[derive(Resource, Debug)]
+#[derive(Resource, GodotResourceCollection, Debug)]
 pub struct MyAssets {
+    #[resource(path = "res://sprite.tscn")]
     pub sprite: ErasedGdResource,
 }
 
-impl Default for MyAssets {
-    fn default() -> Self {
-        let mut resource_loader = ResourceLoader::singleton();
-        let sprite = ErasedGdResource::new(resource_loader.load("sprite.tscn").unwrap());
-
-        Self { sprite }
-    }
-}
-
 fn spawn_sprite(mut commands: Commands, assets: Res<MyAssets>) {
     commands.spawn(
         GodotScene::from_resource(assets.sprite.clone())
@@ -54,10 +48,9 @@ fn spawn_sprite(mut commands: Commands, assets: Res<MyAssets>) {
     );
 }
 
-fn move_sprite(mut sprite: Query<&mut ErasedGd>, mut delta: SystemDeltaTimer) {
-    if let Ok(mut sprite) = sprite.single_mut() {
-        let mut sprite = sprite.get::<Sprite2D>();
-        let delta = delta.delta_seconds() * 20.0;
+fn move_sprite(sprite: GodotNodeQuery<Sprite2D>, time: Res<Time>) {
+    if let Some((_, mut sprite)) = sprite.single() {
+        let delta = time.delta_secs() * 20.0;
         let position = sprite.get_position();
 
         sprite.set_position(Vector2 {
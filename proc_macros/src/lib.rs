@@ -1,26 +1,60 @@
 use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
 use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use syn::{
+    parse_macro_input, AttributeArgs, Data, DeriveInput, Fields, ItemFn, Lit, Meta, NestedMeta,
+};
 
 #[proc_macro_attribute]
-pub fn bevy_app(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn bevy_app(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr_args = parse_macro_input!(attr as AttributeArgs);
     let input_fn = parse_macro_input!(item as ItemFn);
     let name = &input_fn.sig.ident;
+
+    let args = match BevyAppArgs::parse(&attr_args) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    // named apps only register their builder, the unnamed app also defines the extension entry
+    // point
+    if let Some(app_name) = args.name {
+        return quote! {
+            godot::sys::plugin_execute_pre_main!({
+                bevy_godot4::register_named_app(#app_name, #name);
+            });
+
+            #input_fn
+        }
+        .into();
+    }
+
+    let gdextension = match args.entry_symbol {
+        Some(entry_symbol) => quote! { #[gdextension(entry_symbol = #entry_symbol)] },
+        None => quote! { #[gdextension] },
+    };
+    let init_level = args.init_level;
+
     let expanded = quote! {
         struct BevyExtensionLibrary;
 
-        #[gdextension]
+        #gdextension
         unsafe impl ExtensionLibrary for BevyExtensionLibrary {
+            fn min_level() -> godot::prelude::InitLevel {
+                godot::prelude::InitLevel::#init_level
+            }
+
             fn on_level_init(level: godot::prelude::InitLevel) {
-                if level == godot::prelude::InitLevel::Core {
-                    godot::private::class_macros::registry::class::auto_register_classes(level);
-                    let mut app_builder_func = bevy_godot4::APP_BUILDER_FN.lock().unwrap();
-                    if app_builder_func.is_none() {
-                        *app_builder_func = Some(Box::new(#name));
-                    }
+                if level == godot::prelude::InitLevel::#init_level {
+                    bevy_godot4::register_app(#name);
                 }
             }
 
+            fn on_level_deinit(level: godot::prelude::InitLevel) {
+                if level == godot::prelude::InitLevel::#init_level {
+                    bevy_godot4::unregister_apps();
+                }
+            }
         }
 
         #input_fn
@@ -29,3 +63,197 @@ pub fn bevy_app(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
     expanded.into()
 }
+
+#[proc_macro_derive(GodotResourceCollection, attributes(resource))]
+pub fn derive_godot_resource_collection(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &input,
+                    "GodotResourceCollection can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(
+                &input,
+                "GodotResourceCollection can only be derived for structs",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let mut paths = Vec::new();
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = &field.ident;
+        let path = match resource_path(&field.attrs) {
+            Ok(path) => path,
+            Err(error) => return error.to_compile_error().into(),
+        };
+
+        match path {
+            Some(path) => {
+                paths.push(path);
+                initializers.push(quote! {
+                    #ident: resources.next().expect("a resource for every path")
+                });
+            }
+            None => initializers.push(quote! {
+                #ident: ::core::default::Default::default()
+            }),
+        }
+    }
+
+    let expanded = quote! {
+        impl #impl_generics bevy_godot4::prelude::GodotResourceCollection for #name #ty_generics #where_clause {
+            fn resource_paths() -> &'static [&'static str] {
+                &[#(#paths),*]
+            }
+
+            fn from_resources(resources: Vec<bevy_godot4::prelude::ErasedGdResource>) -> Self {
+                let mut resources = resources.into_iter();
+                Self {
+                    #(#initializers),*
+                }
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+#[proc_macro_derive(GodotScriptType)]
+pub fn derive_godot_script_type(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // the fields are converted through reflection, but only structs with named fields become a
+    // `Dictionary`
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(_) | Fields::Unit => {}
+            Fields::Unnamed(_) => {
+                return syn::Error::new_spanned(
+                    &input,
+                    "GodotScriptType can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(
+                &input,
+                "GodotScriptType can only be derived for structs",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    let expanded = quote! {
+        impl #impl_generics bevy_godot4::prelude::GodotScriptType for #name #ty_generics #where_clause {
+            const NAME: &'static str = stringify!(#name);
+        }
+    };
+
+    expanded.into()
+}
+
+/// Parses `#[resource(path = "...")]`
+fn resource_path(attrs: &[syn::Attribute]) -> syn::Result<Option<String>> {
+    let Some(attr) = attrs.iter().find(|attr| attr.path.is_ident("resource")) else {
+        return Ok(None);
+    };
+
+    let error = || syn::Error::new_spanned(attr, "expected #[resource(path = \"...\")]");
+    let Meta::List(list) = attr.parse_meta()? else {
+        return Err(error());
+    };
+
+    match list.nested.first() {
+        Some(NestedMeta::Meta(Meta::NameValue(name_value)))
+            if list.nested.len() == 1 && name_value.path.is_ident("path") =>
+        {
+            match &name_value.lit {
+                Lit::Str(path) => Ok(Some(path.value())),
+                _ => Err(error()),
+            }
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Arguments of `#[bevy_app(...)]`
+struct BevyAppArgs {
+    name: Option<String>,
+    entry_symbol: Option<Ident>,
+    init_level: Ident,
+}
+
+impl BevyAppArgs {
+    // apps are never built in the editor, so the `Editor` level is left out
+    const INIT_LEVELS: [&'static str; 3] = ["Core", "Servers", "Scene"];
+
+    fn parse(args: &AttributeArgs) -> syn::Result<Self> {
+        let mut name = None;
+        let mut entry_symbol = None;
+        let mut init_level = None;
+
+        for arg in args {
+            let NestedMeta::Meta(Meta::NameValue(name_value)) = arg else {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "expected `name = \"...\"`, `entry_symbol = \"...\"` or `init_level = \"...\"`",
+                ));
+            };
+            let Lit::Str(value) = &name_value.lit else {
+                return Err(syn::Error::new_spanned(
+                    &name_value.lit,
+                    "expected a string",
+                ));
+            };
+
+            if name_value.path.is_ident("name") && !value.value().is_empty() {
+                name = Some(value.value());
+            } else if name_value.path.is_ident("entry_symbol") {
+                entry_symbol = Some(value.parse::<Ident>()?);
+            } else if name_value.path.is_ident("init_level")
+                && Self::INIT_LEVELS.contains(&value.value().as_str())
+            {
+                init_level = Some(Ident::new(&value.value(), value.span()));
+            } else {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "expected a non-empty `name`, an `entry_symbol` or an `init_level` of \
+                     \"Core\", \"Servers\" or \"Scene\"",
+                ));
+            }
+        }
+
+        if name.is_some() && (entry_symbol.is_some() || init_level.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "named apps don't define the extension entry point, `entry_symbol` and \
+                 `init_level` go on the unnamed #[bevy_app]",
+            ));
+        }
+
+        Ok(Self {
+            name,
+            entry_symbol,
+            init_level: init_level.unwrap_or_else(|| Ident::new("Scene", Span::call_site())),
+        })
+    }
+}
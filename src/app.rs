@@ -1,25 +1,152 @@
-use bevy::app::App;
+// the code generated for exported properties returns godot's large `CallError`
+#![allow(clippy::result_large_err)]
+
+use bevy::{
+    app::{App, FixedMain, MainScheduleOrder, RunFixedMainLoop},
+    ecs::{schedule::ScheduleLabel, world::World},
+    time::{Fixed, Time, TimeUpdateStrategy, Virtual},
+};
+use bevy::{platform::collections::HashMap, prelude::Resource};
 use godot::{
-    classes::{INode, Node},
-    obj::Base,
-    prelude::{GodotClass, godot_api},
+    builtin::{Dictionary, GString},
+    classes::{INode, InputEvent, Node},
+    obj::{Base, Gd, InstanceId, WithBaseField},
+    prelude::{GodotClass, ToGodot, godot_api, godot_error},
 };
 
 use crate::prelude::*;
 use std::{
-    panic::{AssertUnwindSafe, catch_unwind, resume_unwind},
-    sync::Mutex,
+    cell::RefCell,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
 };
 
+#[doc(hidden)]
+pub type AppBuilderFn = Arc<dyn Fn(&mut App) + Send + Sync>;
+
 lazy_static::lazy_static! {
-    #[doc(hidden)]
-    pub static ref APP_BUILDER_FN: Mutex<Option<Box<dyn Fn(&mut App) + Send>>> = Mutex::new(None);
+    /// App builders by name, the unnamed `#[bevy_app]` is registered as `""`
+    static ref APP_BUILDERS: Mutex<HashMap<String, AppBuilderFn>> = Mutex::new(HashMap::new());
+}
+
+/// Registers the builder of the app named `name`.
+///
+/// # Panics
+/// If an app named `name` is already registered, so that a `BevyApp` node never runs the builder
+/// of another app with the same name.
+#[doc(hidden)]
+pub fn register_app_builder(name: &str, builder: AppBuilderFn) {
+    let mut builders = APP_BUILDERS.lock().unwrap_or_else(PoisonError::into_inner);
+    assert!(
+        !builders.contains_key(name),
+        "an app named \"{name}\" is already registered, app names must be unique"
+    );
+    builders.insert(name.to_string(), builder);
+}
+
+/// Registers `build_app` as the builder of the unnamed app, for projects defining their own
+/// `ExtensionLibrary` instead of using `#[bevy_app]`. Call it from `on_level_init`.
+///
+/// ```ignore
+/// struct MyExtension;
+///
+/// #[gdextension]
+/// unsafe impl ExtensionLibrary for MyExtension {
+///     fn on_level_init(level: InitLevel) {
+///         if level == InitLevel::Scene {
+///             bevy_godot4::register_app(build_app);
+///         }
+///     }
+///
+///     fn on_level_deinit(level: InitLevel) {
+///         if level == InitLevel::Scene {
+///             bevy_godot4::unregister_apps();
+///         }
+///     }
+/// }
+/// ```
+///
+/// # Panics
+/// If the unnamed app is already registered.
+pub fn register_app(build_app: impl Fn(&mut App) + Send + Sync + 'static) {
+    register_named_app("", build_app);
+}
+
+/// Registers `build_app` as the builder of the app named `name`, the equivalent of
+/// `#[bevy_app(name = "...")]`.
+///
+/// # Panics
+/// If an app named `name` is already registered.
+pub fn register_named_app(name: &str, build_app: impl Fn(&mut App) + Send + Sync + 'static) {
+    register_app_builder(name, Arc::new(build_app));
+}
+
+/// Drops the apps of all `BevyApp` nodes and forgets the builder registered by [`register_app`].
+/// Call it from `on_level_deinit`, at the level the app was registered at.
+///
+/// Named apps are registered before `main` and stay registered.
+pub fn unregister_apps() {
+    let nodes = APP_NODES.with_borrow_mut(std::mem::take);
+    for node in nodes {
+        if let Ok(mut node) = Gd::<BevyApp>::try_from_instance_id(node) {
+            node.bind_mut().app = None;
+        }
+    }
+
+    APP_BUILDERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove("");
+}
+
+/// Builds a new app with the builder registered as `name`, without the plugins `BevyApp` adds
+fn run_app_builder(name: &str) -> Option<App> {
+    // the builder is cloned out so that it can register or build other apps
+    let builder = APP_BUILDERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned()?;
+
+    let mut app = App::new();
+    builder(&mut app);
+    Some(app)
 }
 
-#[derive(GodotClass, Default)]
+thread_local! {
+    /// `BevyApp` nodes that have built an app, torn down by [`unregister_apps`]
+    static APP_NODES: RefCell<Vec<InstanceId>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The `BevyApp` node an app belongs to
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct BevyAppNode {
+    pub instance_id: InstanceId,
+    pub app_name: String,
+}
+
+#[derive(GodotClass)]
 #[class(base=Node)]
 pub struct BevyApp {
+    base: Base<Node>,
+    /// Name of the `#[bevy_app(name = "...")]` function building this node's app, the unnamed
+    /// `#[bevy_app]` function if empty.
+    #[export]
+    app_name: GString,
     app: Option<App>,
+    /// Whether the app has been updated since it was built, running its startup schedules
+    started: bool,
+    /// Whether `app` is taken out for an update, which releases the node to GDScript called from
+    /// systems
+    updating: bool,
+    /// Calls GDScript made during the update, applied once it's done
+    script_calls: Vec<ScriptCall>,
+}
+
+enum ScriptCall {
+    SendEvent(String, Dictionary),
+    SetResource(String, Dictionary),
 }
 
 impl BevyApp {
@@ -30,12 +157,228 @@ impl BevyApp {
     pub fn get_app_mut(&mut self) -> Option<&mut App> {
         self.app.as_mut()
     }
+
+    fn build_app(&mut self) {
+        let name = self.app_name.to_string();
+        let Some(mut app) = run_app_builder(&name) else {
+            godot_error!("no #[bevy_app] named \"{name}\" is registered");
+            return;
+        };
+
+        let app_node = BevyAppNode {
+            instance_id: self.base().instance_id(),
+            app_name: name,
+        };
+        app.insert_resource(app_node)
+            .add_plugins(crate::scene::PackedScenePlugin)
+            .add_plugins(crate::signals::GodotSignalsPlugin)
+            .add_plugins(crate::input_event::GodotInputEventPlugin)
+            .add_plugins(crate::lifecycle::GodotLifecyclePlugin)
+            .add_plugins(crate::pause::GodotPausePlugin)
+            .add_plugins(crate::gdscript::GodotScriptPlugin)
+            .add_plugins(crate::entity_node::BevyEntityPlugin)
+            .init_resource::<GodotNodeEntities>()
+            .init_non_send_resource::<crate::scene_tree::SceneTreeRefImpl>();
+
+        #[cfg(feature = "assets")]
+        app.add_plugins(crate::assets::GodotAssetsPlugin);
+
+        // FixedMain is run from physics_process instead, once per Godot physics tick
+        app.world_mut()
+            .resource_mut::<MainScheduleOrder>()
+            .labels
+            .retain(|label| *label != RunFixedMainLoop.intern());
+
+        if let Some(process_mode) = app.world().resource::<GodotPauseSettings>().process_mode {
+            self.base_mut().set_process_mode(process_mode);
+        }
+
+        self.app = Some(app);
+        self.started = false;
+    }
+
+    /// Runs `update`, applying the [`PanicPolicy`] if it panics. Returns whether `update` ran
+    /// without panicking.
+    fn update_app(&mut self, update: impl FnOnce(&mut App)) -> bool {
+        let Some(mut app) = self.app.take() else {
+            return false;
+        };
+
+        // the node stays unbound while the app updates, so that GDScript called from systems can
+        // call back into it
+        self.updating = true;
+        let result = {
+            let _guard = self.base_mut();
+            catch_unwind(AssertUnwindSafe(|| update(&mut app)))
+        };
+        self.updating = false;
+
+        let Err(payload) = result else {
+            self.app = Some(app);
+            self.apply_script_calls();
+            return true;
+        };
+
+        let panic = GodotPanic::new(payload.as_ref());
+        godot_error!("bevy app update panicked: {panic}");
+
+        if let Some(hook) = app.world().get_resource::<GodotPanicHook>() {
+            (hook.0)(&panic);
+        }
+
+        match app
+            .world()
+            .get_resource::<PanicPolicy>()
+            .copied()
+            .unwrap_or_default()
+        {
+            PanicPolicy::Crash => std::process::abort(),
+            PanicPolicy::StopApp => self.script_calls.clear(),
+            PanicPolicy::RebuildApp => {
+                self.script_calls.clear();
+                self.build_app();
+            }
+        }
+
+        false
+    }
+
+    /// Applies the `send_event` and `set_resource` calls made during the last update
+    fn apply_script_calls(&mut self) {
+        let Some(app) = self.app.as_mut() else {
+            return;
+        };
+
+        for call in std::mem::take(&mut self.script_calls) {
+            let result = match call {
+                ScriptCall::SendEvent(name, data) => {
+                    crate::gdscript::send_script_event(app.world_mut(), &name, &data)
+                }
+                ScriptCall::SetResource(name, data) => {
+                    crate::gdscript::set_script_resource(app.world_mut(), &name, &data)
+                }
+            };
+
+            if let Err(error) = result {
+                godot_error!("{error}");
+            }
+        }
+    }
+
+    fn send_input_event(&mut self, event_type: GodotInputEventType, event: Gd<InputEvent>) {
+        if let Some(app) = self.app.as_mut() {
+            app.world_mut()
+                .send_event(GodotInputEvent::new(event_type, event));
+        }
+    }
+}
+
+#[godot_api]
+impl BevyApp {
+    /// Emitted for every event registered with `emit_script_event`, at the end of the frame.
+    #[signal]
+    fn bevy_event(name: GString, data: Dictionary);
+
+    /// Writes the event registered with `register_script_event` as `name`. Returns whether the
+    /// event was written.
+    ///
+    /// Calls made by systems during an update are written once the update is done, and return
+    /// `true`.
+    #[func]
+    fn send_event(&mut self, name: GString, data: Dictionary) -> bool {
+        if self.updating {
+            self.script_calls
+                .push(ScriptCall::SendEvent(name.to_string(), data));
+            return true;
+        }
+
+        let Some(app) = self.app.as_mut() else {
+            return false;
+        };
+
+        crate::gdscript::send_script_event(app.world_mut(), &name.to_string(), &data)
+            .inspect_err(|error| godot_error!("{error}"))
+            .is_ok()
+    }
+
+    /// Returns the resource registered with `register_script_resource` as `name`, or an empty
+    /// `Dictionary` if it doesn't exist.
+    ///
+    /// The world can't be read during an update, calls made by systems return an empty
+    /// `Dictionary`.
+    #[func]
+    fn get_resource(&self, name: GString) -> Dictionary {
+        if self.updating {
+            godot_error!(
+                "the resource \"{name}\" can't be read while the app updates, pass it to the \
+                 script from the system instead"
+            );
+            return Dictionary::new();
+        }
+
+        let Some(app) = self.app.as_ref() else {
+            return Dictionary::new();
+        };
+
+        crate::gdscript::get_script_resource(app.world(), &name.to_string())
+            .inspect_err(|error| godot_error!("{error}"))
+            .unwrap_or_default()
+    }
+
+    /// Replaces the resource registered with `register_script_resource` as `name`. Returns
+    /// whether the resource was replaced.
+    ///
+    /// Calls made by systems during an update are applied once the update is done, and return
+    /// `true`.
+    #[func]
+    fn set_resource(&mut self, name: GString, data: Dictionary) -> bool {
+        if self.updating {
+            self.script_calls
+                .push(ScriptCall::SetResource(name.to_string(), data));
+            return true;
+        }
+
+        let Some(app) = self.app.as_mut() else {
+            return false;
+        };
+
+        crate::gdscript::set_script_resource(app.world_mut(), &name.to_string(), &data)
+            .inspect_err(|error| godot_error!("{error}"))
+            .is_ok()
+    }
+
+    /// Emits `bevy_event` for the events queued during the last update
+    fn emit_script_events(&mut self) {
+        let Some(app) = self.app.as_mut() else {
+            return;
+        };
+        let Some(mut queue) = app
+            .world_mut()
+            .get_non_send_resource_mut::<crate::gdscript::GodotScriptEventQueue>()
+        else {
+            return;
+        };
+        let events = std::mem::take(&mut queue.0);
+
+        // the app isn't borrowed while emitting, so handlers can call back into this node
+        for (name, data) in events {
+            self.base_mut()
+                .emit_signal("bevy_event", &[name.to_variant(), data.to_variant()]);
+        }
+    }
 }
 
 #[godot_api]
 impl INode for BevyApp {
-    fn init(_base: Base<Node>) -> Self {
-        Default::default()
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            app_name: GString::new(),
+            app: None,
+            started: false,
+            updating: false,
+            script_calls: Vec::new(),
+        }
     }
 
     fn ready(&mut self) {
@@ -43,59 +386,157 @@ impl INode for BevyApp {
             return;
         }
 
-        let mut app = App::new();
-        (APP_BUILDER_FN.lock().unwrap().as_mut().unwrap())(&mut app);
-        app.add_plugins(bevy::app::TaskPoolPlugin::default())
-            .add_plugins(bevy::log::LogPlugin::default())
-            .add_plugins(bevy::diagnostic::FrameCountPlugin)
-            .add_plugins(bevy::diagnostic::DiagnosticsPlugin)
-            .add_plugins(bevy::time::TimePlugin)
-            .add_plugins(crate::scene::PackedScenePlugin)
-            .init_non_send_resource::<crate::scene_tree::SceneTreeRefImpl>();
-        // .add_plugins(GodotSignalsPlugin)
-        // .add_plugins(GodotInputEventPlugin);
-
-        #[cfg(feature = "assets")]
-        app.add_plugins(crate::assets::GodotAssetsPlugin);
+        crate::panic::install_panic_hook();
+        let instance_id = self.base().instance_id();
+        APP_NODES.with_borrow_mut(|nodes| {
+            nodes.retain(|node| {
+                *node != instance_id && Gd::<BevyApp>::try_from_instance_id(*node).is_ok()
+            });
+            nodes.push(instance_id);
+        });
 
-        self.app = Some(app);
+        self.build_app();
     }
 
-    fn process(&mut self, _delta: f64) {
+    fn process(&mut self, delta: f64) {
         if godot::classes::Engine::singleton().is_editor_hint() {
             return;
         }
 
-        if let Some(app) = self.app.as_mut() {
+        if self.update_app(|app| {
             app.insert_resource(GodotVisualFrame);
+            set_frame_delta(app.world_mut(), delta);
+            app.update();
+        }) {
+            self.started = true;
+        }
 
-            if let Err(e) = catch_unwind(AssertUnwindSafe(|| app.update())) {
-                self.app = None;
-
-                eprintln!("bevy app update panicked");
-                resume_unwind(e);
-            }
-
+        if let Some(app) = self.app.as_mut() {
             app.world_mut().remove_resource::<GodotVisualFrame>();
         }
+
+        self.emit_script_events();
+    }
+
+    fn input(&mut self, event: Gd<InputEvent>) {
+        self.send_input_event(GodotInputEventType::Input, event);
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        self.send_input_event(GodotInputEventType::UnhandledInput, event);
     }
 
-    fn physics_process(&mut self, _delta: f64) {
+    fn physics_process(&mut self, delta: f64) {
         if godot::classes::Engine::singleton().is_editor_hint() {
             return;
         }
 
-        if let Some(app) = self.app.as_mut() {
+        // wait for the first update to run the startup schedules
+        if !self.started {
+            return;
+        }
+
+        self.update_app(|app| {
             app.insert_resource(GodotPhysicsFrame);
+            run_physics_update(app.world_mut(), delta);
+        });
 
-            if let Err(e) = catch_unwind(AssertUnwindSafe(|| app.update())) {
-                self.app = None;
+        if let Some(app) = self.app.as_mut() {
+            app.world_mut().remove_resource::<GodotPhysicsFrame>();
+        }
+    }
+}
 
-                eprintln!("bevy app update panicked");
-                resume_unwind(e);
-            }
+/// Makes the next [`Time`] update advance [`Time<Virtual>`] by Godot's frame delta, which is
+/// scaled by `Engine.time_scale`, and [`Time<Real>`] by the unscaled delta
+fn set_frame_delta(world: &mut World, delta: f64) {
+    let time_scale = godot::classes::Engine::singleton()
+        .get_time_scale()
+        .max(0.0);
+    let Some(mut virtual_time) = world.get_resource_mut::<Time<Virtual>>() else {
+        return;
+    };
+    virtual_time.set_relative_speed_f64(time_scale);
 
-            app.world_mut().remove_resource::<GodotPhysicsFrame>();
+    // the unscaled delta can't be recovered while time is stopped, fall back to the system clock
+    let strategy = if time_scale > 0.0 {
+        TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(delta / time_scale))
+    } else {
+        TimeUpdateStrategy::Automatic
+    };
+    world.insert_resource(strategy);
+}
+
+/// Runs [`FixedMain`] once, with [`Time<Fixed>`] advanced by Godot's physics delta. Nothing runs
+/// while the scene tree or [`Time<Virtual>`] is paused.
+fn run_physics_update(world: &mut World, delta: f64) {
+    let paused = world
+        .get_resource::<GodotPaused>()
+        .is_some_and(|paused| paused.0)
+        || world
+            .get_resource::<Time<Virtual>>()
+            .is_some_and(|time| time.is_paused());
+    if paused {
+        return;
+    }
+
+    let Some(mut fixed_time) = world.get_resource_mut::<Time<Fixed>>() else {
+        world.run_schedule(FixedMain);
+        return;
+    };
+
+    let delta = Duration::from_secs_f64(delta);
+    if !delta.is_zero() {
+        fixed_time.set_timestep(delta);
+    }
+    fixed_time.advance_by(delta);
+
+    *world.resource_mut::<Time>() = world.resource::<Time<Fixed>>().as_generic();
+    world.run_schedule(FixedMain);
+    *world.resource_mut::<Time>() = world.resource::<Time<Virtual>>().as_generic();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{app::Update, prelude::ResMut};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Resource, Default)]
+    struct Updates(u32);
+
+    fn panic_once(mut updates: ResMut<Updates>) {
+        static PANICKED: AtomicBool = AtomicBool::new(false);
+        if !PANICKED.swap(true, Ordering::SeqCst) {
+            panic!("first update");
         }
+
+        updates.0 += 1;
+    }
+
+    #[test]
+    fn panicked_app_is_rebuilt() {
+        register_named_app("panic_once", |app: &mut App| {
+            app.init_resource::<Updates>()
+                .add_systems(Update, panic_once);
+        });
+
+        let mut app = run_app_builder("panic_once").unwrap();
+        assert!(catch_unwind(AssertUnwindSafe(|| app.update())).is_err());
+
+        // the schedules that were running are lost with the unwind, which is why there is no
+        // policy keeping the app
+        assert!(catch_unwind(AssertUnwindSafe(|| app.update())).is_err());
+
+        let mut app = run_app_builder("panic_once").unwrap();
+        app.update();
+        assert_eq!(app.world().resource::<Updates>().0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "already registered")]
+    fn duplicate_app_names_panic() {
+        register_named_app("duplicate", |_: &mut App| {});
+        register_named_app("duplicate", |_: &mut App| {});
     }
 }
@@ -1,75 +1,167 @@
 use crate::prelude::*;
 use bevy::{
-    asset::{AssetLoader, LoadContext, LoadedAsset},
-    utils::BoxedFuture,
+    app::{App, Plugin},
+    asset::{
+        AssetApp, AssetLoader, AssetMetaCheck, AssetPath, AssetPlugin, LoadContext,
+        io::{
+            AssetReader, AssetReaderError, AssetSource, AssetSourceId, PathStream, Reader,
+            VecReader,
+        },
+    },
+    tasks::futures_lite::stream,
 };
-use godot::engine::{resource_loader::CacheMode, ResourceLoader};
+use godot::{
+    classes::{DirAccess, FileAccess, ResourceLoader, resource_loader::CacheMode},
+    global::Error,
+};
+use std::path::{Path, PathBuf};
 
 pub struct GodotAssetsPlugin;
 impl Plugin for GodotAssetsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(AssetPlugin {
-            asset_folder: std::env::current_dir()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string(),
-            watch_for_changes: false,
+        // asset sources have to be registered before the AssetPlugin
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build().with_reader(|| Box::new(GodotAssetReader::new("res://"))),
+        )
+        .register_asset_source(
+            "res",
+            AssetSource::build().with_reader(|| Box::new(GodotAssetReader::new("res://"))),
+        )
+        .register_asset_source(
+            "user",
+            AssetSource::build().with_reader(|| Box::new(GodotAssetReader::new("user://"))),
+        )
+        .add_plugins(AssetPlugin {
+            meta_check: AssetMetaCheck::Never,
+            ..Default::default()
         })
-        .add_asset::<ErasedGdResource>()
+        .init_asset::<ErasedGdResource>()
         .init_asset_loader::<GodotResourceLoader>();
     }
 }
 
+/// Bevy [`AssetReader`] that reads files through Godot's [`FileAccess`] and [`DirAccess`].
+///
+/// This is registered as the default asset source as well as the `res://` and `user://` sources,
+/// so any Bevy [`AssetLoader`] can load files from the project, including files packed into a
+/// `.pck` by a Godot export.
+pub struct GodotAssetReader {
+    root: &'static str,
+}
+
+impl GodotAssetReader {
+    /// Creates a reader for paths relative to `root`, e.g. `"res://"`.
+    pub fn new(root: &'static str) -> Self {
+        Self { root }
+    }
+
+    fn godot_path(&self, path: &Path) -> String {
+        format!("{}{}", self.root, path.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+impl AssetReader for GodotAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let godot_path = self.godot_path(path);
+        let bytes = FileAccess::get_file_as_bytes(&godot_path);
+
+        if FileAccess::get_open_error() == Error::OK {
+            return Ok(VecReader::new(bytes.to_vec()));
+        }
+
+        // imported resources are remapped on export, so the original file may not exist even
+        // though Godot can still load it. Loaders like GodotResourceLoader ignore the bytes.
+        if ResourceLoader::singleton().exists(&godot_path) {
+            return Ok(VecReader::new(Vec::new()));
+        }
+
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let mut meta_path = path.as_os_str().to_owned();
+        meta_path.push(".meta");
+        let meta_path = PathBuf::from(meta_path);
+
+        let bytes = FileAccess::get_file_as_bytes(&self.godot_path(&meta_path));
+        if FileAccess::get_open_error() != Error::OK {
+            return Err(AssetReaderError::NotFound(meta_path));
+        }
+
+        Ok(VecReader::new(bytes.to_vec()))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let godot_path = self.godot_path(path);
+        if !DirAccess::dir_exists_absolute(&godot_path) {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        }
+
+        let entries: Vec<PathBuf> = DirAccess::get_directories_at(&godot_path)
+            .as_slice()
+            .iter()
+            .chain(DirAccess::get_files_at(&godot_path).as_slice())
+            .map(|name| path.join(name.to_string()))
+            .collect();
+
+        Ok(Box::new(stream::iter(entries)))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(DirAccess::dir_exists_absolute(&self.godot_path(path)))
+    }
+}
+
 /// Allow for loading godot resources via Bevy's assets framework, can be used with bevy_asset_loader
 ///
-/// This is not a recommended feature due to issues with referencing a PackedScene resource 
-/// simultaneously in Godot during loading - and there currently isn't an easy way to make asset 
+/// This is not a recommended feature due to issues with referencing a PackedScene resource
+/// simultaneously in Godot during loading - and there currently isn't an easy way to make asset
 /// loading into a NonSend Bevy Resource single-threaded.
 #[derive(Default)]
 pub struct GodotResourceLoader;
 
 impl AssetLoader for GodotResourceLoader {
+    type Asset = ErasedGdResource;
+    type Settings = ();
+    type Error = anyhow::Error;
+
     fn extensions(&self) -> &[&str] {
         &["tscn", "scn", "res", "tres", "jpg", "png"]
     }
 
-    fn load<'a>(
-        &'a self,
-        _bytes: &'a [u8],
-        load_context: &'a mut LoadContext,
-    ) -> BoxedFuture<'a, anyhow::Result<(), anyhow::Error>> {
-        Box::pin(async {
-            let mut load_asset = || {
-                let mut resource_loader = ResourceLoader::singleton();
-                let loaded = resource_loader
-                    .load(
-                        ("res://".to_owned()
-                            + load_context.path().to_str().ok_or_else(|| {
-                                anyhow::anyhow!("failed to convert asset path to string")
-                            })?)
-                        .into(),
-                        "".into(),
-                        CacheMode::CACHE_MODE_REUSE,
-                    )
-                    .ok_or_else(|| {
-                        anyhow::anyhow!("failed to load asset {}", load_context.path().display())
-                    })?;
-
-                load_context.set_default_asset(LoadedAsset::new(ErasedGdResource::new(loaded)));
-                Ok(())
-            };
-
-            if let Err(e) = load_asset() {
-                eprintln!(
-                    "loading {} asset failed: {}",
-                    load_context.path().to_str().unwrap(),
-                    e
-                );
-                return Err(e);
-            }
-
-            Ok(())
-        })
+    async fn load(
+        &self,
+        _reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        // the asset server logs the error
+        let path = godot_path(load_context.asset_path())?;
+
+        let loaded = ResourceLoader::singleton()
+            .load_ex(&path)
+            .cache_mode(CacheMode::REUSE)
+            .done()
+            .ok_or_else(|| anyhow::anyhow!("failed to load asset {path}"))?;
+
+        Ok(ErasedGdResource::new(loaded))
     }
 }
+
+/// Converts an asset path into a Godot path, e.g. `user://save.tres` for the `user` source and
+/// `res://...` for the default source.
+fn godot_path(asset_path: &AssetPath) -> anyhow::Result<String> {
+    let path = asset_path
+        .path()
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("failed to convert asset path to string"))?;
+
+    Ok(match asset_path.source() {
+        AssetSourceId::Default => format!("res://{path}"),
+        AssetSourceId::Name(source) => format!("{source}://{path}"),
+    })
+}
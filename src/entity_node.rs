@@ -0,0 +1,227 @@
+use crate::{app::BevyAppNode, prelude::*};
+use bevy::{
+    app::{App, First, Plugin},
+    ecs::{component::Component, reflect::ReflectComponent, world::World},
+    log::tracing,
+    prelude::{AppTypeRegistry, IntoScheduleConfigs, ReflectDefault, ReflectFromReflect},
+    reflect::{PartialReflect, TypeRegistration, TypeRegistry},
+};
+use godot::{
+    builtin::{GString, Variant},
+    classes::Node,
+    obj::{Gd, InstanceId},
+};
+use std::cell::RefCell;
+
+pub(crate) struct BevyEntityPlugin;
+impl Plugin for BevyEntityPlugin {
+    fn build(&self, app: &mut App) {
+        // a node matching the registration filters gets its entity from the registration first
+        app.add_systems(
+            First,
+            spawn_bevy_entities.after(crate::node_registration::RegisterNodesSet),
+        );
+    }
+}
+
+pub use class::BevyEntity;
+
+// the code `#[derive(GodotClass)]` generates for `#[export]` fields returns godot's large
+// `CallError`, and an allow on the struct doesn't reach it
+#[allow(clippy::result_large_err)]
+mod class {
+    use super::PENDING_ENTITIES;
+    use godot::{
+        builtin::{Dictionary, GString},
+        classes::{INode, Node},
+        obj::{Base, WithBaseField},
+        prelude::{GodotClass, godot_api},
+    };
+
+    /// A node carrying Bevy components, so that `.tscn` files can declare gameplay data without
+    /// Rust code per scene.
+    ///
+    /// When the node enters the scene tree, the app spawns an entity for it with an
+    /// [`ErasedGd`](crate::prelude::ErasedGd) and a
+    /// [`GodotLifecycle`](crate::prelude::GodotLifecycle), or uses the node's existing entity, and
+    /// inserts the `components`. They are inserted once per node, entering the tree again after a
+    /// reparent keeps the entity's current values.
+    #[derive(GodotClass)]
+    #[class(base=Node)]
+    pub struct BevyEntity {
+        base: Base<Node>,
+        /// Components by type name, either the short name (`"Health"`) or the full type path.
+        /// Values are converted with [`apply_variant`](crate::prelude::apply_variant), so structs
+        /// are `Dictionary`s with an entry per field. Components must be registered with
+        /// `#[reflect(Component)]`, and with `#[reflect(Default)]` to leave out fields.
+        #[export]
+        pub(super) components: Dictionary,
+        /// The `app_name` of the `BevyApp` that spawns the entity.
+        #[export]
+        pub(super) app_name: GString,
+    }
+
+    #[godot_api]
+    impl INode for BevyEntity {
+        fn init(base: Base<Node>) -> Self {
+            Self {
+                base,
+                components: Dictionary::new(),
+                app_name: GString::new(),
+            }
+        }
+
+        fn enter_tree(&mut self) {
+            if godot::classes::Engine::singleton().is_editor_hint() {
+                return;
+            }
+
+            let instance_id = self.base().instance_id();
+            PENDING_ENTITIES.with_borrow_mut(|pending| pending.push(instance_id));
+        }
+    }
+}
+
+thread_local! {
+    /// `BevyEntity` nodes that entered the tree and haven't been spawned by their app yet
+    static PENDING_ENTITIES: RefCell<Vec<InstanceId>> = const { RefCell::new(Vec::new()) };
+}
+
+fn spawn_bevy_entities(world: &mut World) {
+    let app_name = world.resource::<BevyAppNode>().app_name.clone();
+    // looked up once a node names another app
+    let mut app_names: Option<Vec<String>> = None;
+    let nodes = PENDING_ENTITIES.with_borrow_mut(|pending| {
+        let mut nodes = Vec::new();
+        pending.retain(|instance_id| {
+            let Ok(node) = Gd::<BevyEntity>::try_from_instance_id(*instance_id) else {
+                return false;
+            };
+
+            if !node.is_inside_tree() {
+                return false;
+            }
+
+            let node_app_name = node.bind().app_name.to_string();
+            if node_app_name == app_name {
+                nodes.push(node);
+                return false;
+            }
+
+            // left for the app it names, unless there is none
+            let app_names = app_names.get_or_insert_with(|| bevy_app_names(&node));
+            if app_names.contains(&node_app_name) {
+                return true;
+            }
+
+            tracing::warn!(
+                "no BevyApp named \"{node_app_name}\" is in the scene tree, {} isn't spawned",
+                node.get_path()
+            );
+            false
+        });
+        nodes
+    });
+
+    for node in nodes {
+        spawn_bevy_entity(world, node);
+    }
+}
+
+/// The `app_name`s of the `BevyApp` nodes in the scene tree of `node`
+fn bevy_app_names(node: &Gd<BevyEntity>) -> Vec<String> {
+    let Some(root) = node.get_tree().and_then(|tree| tree.get_root()) else {
+        return Vec::new();
+    };
+
+    root.find_children_ex("*")
+        .type_("BevyApp")
+        .owned(false)
+        .done()
+        .iter_shared()
+        .map(|app| app.get("app_name").to::<GString>().to_string())
+        .collect()
+}
+
+/// Marks an entity whose `BevyEntity` node already had its `components` inserted
+#[derive(Component)]
+struct BevyEntityComponentsApplied;
+
+fn spawn_bevy_entity(world: &mut World, node: Gd<BevyEntity>) {
+    let components = node.bind().components.clone();
+    let node = node.upcast::<Node>();
+
+    let existing = world
+        .resource::<GodotNodeEntities>()
+        .get(node.instance_id());
+    let entity = match existing {
+        Some(entity) if world.get::<BevyEntityComponentsApplied>(entity).is_some() => return,
+        Some(entity) => entity,
+        None => world
+            .spawn((
+                crate::node_registration::node_components(node.clone()),
+                GodotLifecycle::default(),
+            ))
+            .id(),
+    };
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    for (name, data) in components.iter_shared() {
+        let name = name.to_string();
+        let component = build_component(&type_registry, &name, &data);
+        let (registration, component) = match component {
+            Ok(component) => component,
+            Err(error) => {
+                tracing::error!(
+                    "failed to add the component \"{name}\" of {}: {error}",
+                    node.get_path()
+                );
+                continue;
+            }
+        };
+
+        let reflect_component = registration.data::<ReflectComponent>().unwrap();
+        reflect_component.insert(
+            &mut world.entity_mut(entity),
+            component.as_ref(),
+            &type_registry,
+        );
+    }
+
+    world.entity_mut(entity).insert(BevyEntityComponentsApplied);
+}
+
+/// Builds the component named `name` from its `Variant`
+fn build_component<'a>(
+    type_registry: &'a TypeRegistry,
+    name: &str,
+    data: &Variant,
+) -> Result<(&'a TypeRegistration, Box<dyn PartialReflect>), String> {
+    let registration = type_registry
+        .get_with_short_type_path(name)
+        .or_else(|| type_registry.get_with_type_path(name))
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .ok_or("no component with this name is registered with #[reflect(Component)]")?;
+
+    let dynamic = variant_to_dynamic(data, registration.type_info()).map_err(|e| e.to_string())?;
+
+    // fields left out of the Dictionary are taken from the default value
+    let component = match registration.data::<ReflectDefault>() {
+        Some(reflect_default) => {
+            let mut component = reflect_default.default();
+            component
+                .try_apply(dynamic.as_ref())
+                .map_err(|e| e.to_string())?;
+            component.into_partial_reflect()
+        }
+        None => registration
+            .data::<ReflectFromReflect>()
+            .and_then(|from_reflect| from_reflect.from_reflect(dynamic.as_ref()))
+            .ok_or("missing fields, add #[reflect(Default)] to the component to leave them out")?
+            .into_partial_reflect(),
+    };
+
+    Ok((registration, component))
+}
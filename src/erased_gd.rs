@@ -1,4 +1,8 @@
-use bevy::prelude::Component;
+use bevy::{
+    ecs::{component::HookContext, world::DeferredWorld},
+    platform::collections::HashMap,
+    prelude::{Component, Entity},
+};
 use godot::{
     classes::{Node, Object, Resource},
     obj::{Bounds, Gd, GodotClass, Inherits, InstanceId, RawGd, bounds::DynMemory},
@@ -6,11 +10,49 @@ use godot::{
 };
 
 #[derive(Debug, Component, Clone)]
+#[component(on_insert = index_node, on_replace = unindex_node)]
 pub struct ErasedGd {
     instance_id: InstanceId,
 }
 
+/// Bevy Resource that maps Godot nodes to the entities holding them as an [`ErasedGd`]
+#[derive(Debug, Default, bevy::prelude::Resource)]
+pub struct GodotNodeEntities(HashMap<InstanceId, Entity>);
+
+impl GodotNodeEntities {
+    /// Returns the entity whose [`ErasedGd`] holds the node with the given instance id
+    pub fn get(&self, instance_id: InstanceId) -> Option<Entity> {
+        self.0.get(&instance_id).copied()
+    }
+}
+
+fn index_node(mut world: DeferredWorld, context: HookContext) {
+    let instance_id = world.get::<ErasedGd>(context.entity).unwrap().instance_id;
+    if let Some(mut entities) = world.get_resource_mut::<GodotNodeEntities>() {
+        entities.0.insert(instance_id, context.entity);
+    }
+
+    crate::node::insert_godot_nodes(&mut world, context.entity, instance_id);
+}
+
+fn unindex_node(mut world: DeferredWorld, context: HookContext) {
+    crate::node::remove_godot_nodes(&mut world, context.entity);
+
+    let instance_id = world.get::<ErasedGd>(context.entity).unwrap().instance_id;
+    let Some(mut entities) = world.get_resource_mut::<GodotNodeEntities>() else {
+        return;
+    };
+
+    if entities.0.get(&instance_id) == Some(&context.entity) {
+        entities.0.remove(&instance_id);
+    }
+}
+
 impl ErasedGd {
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
     pub fn get<T: Inherits<Node>>(&mut self) -> Gd<T> {
         self.try_get()
             .unwrap_or_else(|| panic!("failed to get godot ref as {}", std::any::type_name::<T>()))
@@ -37,6 +79,10 @@ impl ErasedGd {
 }
 
 #[derive(Debug, bevy::prelude::Resource)]
+#[cfg_attr(
+    feature = "assets",
+    derive(bevy::asset::Asset, bevy::reflect::TypePath)
+)]
 pub struct ErasedGdResource {
     resource_id: InstanceId,
 }
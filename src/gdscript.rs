@@ -0,0 +1,197 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, Last, Plugin},
+    ecs::world::World,
+    log::tracing,
+    platform::collections::HashMap,
+    prelude::{Event, EventReader, NonSendMut, Resource},
+    reflect::{FromReflect, Reflect, Typed},
+};
+use godot::{builtin::Dictionary, meta::ToGodot};
+
+pub(crate) struct GodotScriptPlugin;
+impl Plugin for GodotScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GodotScriptRegistry>()
+            .init_non_send_resource::<GodotScriptEventQueue>();
+    }
+}
+
+/// A type GDScript can read and write as a `Dictionary` with an entry per field, see
+/// [`GodotScriptAppExt`].
+///
+/// Derive it along with `Reflect` for structs with named fields. Values are converted with
+/// [`reflect_to_variant`] and [`reflect_from_variant`], so fields can be of any type those
+/// support, and fields left out of the `Dictionary` are taken from `Default` with
+/// `#[reflect(Default)]`.
+pub trait GodotScriptType: Reflect + FromReflect + Typed {
+    /// The name GDScript refers to the type by, the name of the struct when derived.
+    const NAME: &'static str;
+
+    fn to_dictionary(&self) -> Result<Dictionary, VariantConversionError> {
+        let variant = reflect_to_variant(self.as_partial_reflect())?;
+        variant
+            .try_to::<Dictionary>()
+            .map_err(|_| VariantConversionError::Mismatch {
+                expected: "Dictionary".to_string(),
+                found: variant.get_type(),
+            })
+    }
+
+    fn from_dictionary(dictionary: &Dictionary) -> Result<Self, VariantConversionError> {
+        reflect_from_variant(&dictionary.to_variant())
+    }
+}
+
+/// Adds methods to [`App`] exposing events and resources to GDScript through the `BevyApp` node.
+pub trait GodotScriptAppExt {
+    /// Lets GDScript write the event `E` with `BevyApp.send_event(name, data)`.
+    fn register_script_event<E: Event + GodotScriptType>(&mut self) -> &mut Self;
+
+    /// Emits the `bevy_event(name, data)` signal of the `BevyApp` node for every event `E`
+    /// written in Bevy, at the end of the frame.
+    fn emit_script_event<E: Event + GodotScriptType>(&mut self) -> &mut Self;
+
+    /// Lets GDScript read the resource `R` with `BevyApp.get_resource(name)` and replace it with
+    /// `BevyApp.set_resource(name, data)`.
+    fn register_script_resource<R: Resource + GodotScriptType>(&mut self) -> &mut Self;
+}
+
+impl GodotScriptAppExt for App {
+    fn register_script_event<E: Event + GodotScriptType>(&mut self) -> &mut Self {
+        self.add_event::<E>()
+            .init_resource::<GodotScriptRegistry>()
+            .world_mut()
+            .resource_mut::<GodotScriptRegistry>()
+            .events
+            .insert(E::NAME, write_script_event::<E>);
+        self
+    }
+
+    fn emit_script_event<E: Event + GodotScriptType>(&mut self) -> &mut Self {
+        self.add_event::<E>()
+            .init_non_send_resource::<GodotScriptEventQueue>()
+            .add_systems(Last, queue_script_events::<E>)
+    }
+
+    fn register_script_resource<R: Resource + GodotScriptType>(&mut self) -> &mut Self {
+        self.init_resource::<GodotScriptRegistry>()
+            .world_mut()
+            .resource_mut::<GodotScriptRegistry>()
+            .resources
+            .insert(
+                R::NAME,
+                ScriptResourceAccess {
+                    get: read_script_resource::<R>,
+                    set: write_script_resource::<R>,
+                },
+            );
+        self
+    }
+}
+
+/// Events and resources registered with [`GodotScriptAppExt`], by name
+#[derive(Resource, Default)]
+pub(crate) struct GodotScriptRegistry {
+    events: HashMap<&'static str, ScriptWriteFn>,
+    resources: HashMap<&'static str, ScriptResourceAccess>,
+}
+
+/// Writes an event or resource from a `Dictionary`, returns an error message if it doesn't match
+type ScriptWriteFn = fn(&mut World, &Dictionary) -> Result<(), String>;
+
+#[derive(Clone, Copy)]
+struct ScriptResourceAccess {
+    get: fn(&World) -> Result<Dictionary, String>,
+    set: ScriptWriteFn,
+}
+
+/// Events waiting to be emitted as `bevy_event` signals
+#[derive(Default)]
+pub(crate) struct GodotScriptEventQueue(pub Vec<(&'static str, Dictionary)>);
+
+/// Writes the event named `name`, returns an error message if it isn't registered or `data`
+/// doesn't match it
+pub(crate) fn send_script_event(
+    world: &mut World,
+    name: &str,
+    data: &Dictionary,
+) -> Result<(), String> {
+    let write = world
+        .get_resource::<GodotScriptRegistry>()
+        .and_then(|registry| registry.events.get(name).copied())
+        .ok_or_else(|| format!("no event named \"{name}\" is registered for GDScript"))?;
+
+    write(world, data)
+}
+
+/// Returns the resource named `name` as a `Dictionary`
+pub(crate) fn get_script_resource(world: &World, name: &str) -> Result<Dictionary, String> {
+    let access = script_resource_access(world, name)?;
+    (access.get)(world)
+}
+
+/// Replaces the resource named `name` with `data`
+pub(crate) fn set_script_resource(
+    world: &mut World,
+    name: &str,
+    data: &Dictionary,
+) -> Result<(), String> {
+    let access = script_resource_access(world, name)?;
+    (access.set)(world, data)
+}
+
+fn script_resource_access(world: &World, name: &str) -> Result<ScriptResourceAccess, String> {
+    world
+        .get_resource::<GodotScriptRegistry>()
+        .and_then(|registry| registry.resources.get(name).copied())
+        .ok_or_else(|| format!("no resource named \"{name}\" is registered for GDScript"))
+}
+
+fn write_script_event<E: Event + GodotScriptType>(
+    world: &mut World,
+    data: &Dictionary,
+) -> Result<(), String> {
+    let event = E::from_dictionary(data)
+        .map_err(|error| format!("{data} doesn't match the event \"{}\": {error}", E::NAME))?;
+
+    world.send_event(event);
+    Ok(())
+}
+
+fn read_script_resource<R: Resource + GodotScriptType>(
+    world: &World,
+) -> Result<Dictionary, String> {
+    let resource = world
+        .get_resource::<R>()
+        .ok_or_else(|| format!("the resource \"{}\" doesn't exist", R::NAME))?;
+
+    resource
+        .to_dictionary()
+        .map_err(|error| format!("failed to convert the resource \"{}\": {error}", R::NAME))
+}
+
+fn write_script_resource<R: Resource + GodotScriptType>(
+    world: &mut World,
+    data: &Dictionary,
+) -> Result<(), String> {
+    let resource = R::from_dictionary(data)
+        .map_err(|error| format!("{data} doesn't match the resource \"{}\": {error}", R::NAME))?;
+
+    world.insert_resource(resource);
+    Ok(())
+}
+
+fn queue_script_events<E: Event + GodotScriptType>(
+    mut events: EventReader<E>,
+    mut queue: NonSendMut<GodotScriptEventQueue>,
+) {
+    for event in events.read() {
+        match event.to_dictionary() {
+            Ok(data) => queue.0.push((E::NAME, data)),
+            Err(error) => {
+                tracing::error!("failed to convert the event \"{}\": {error}", E::NAME);
+            }
+        }
+    }
+}
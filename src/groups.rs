@@ -0,0 +1,85 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, PreUpdate},
+    platform::collections::HashSet,
+    prelude::{Commands, Component, Entity, Query, Res, ResMut, Resource, With},
+};
+use std::marker::PhantomData;
+
+/// Adds [`register_godot_group`](GodotGroupAppExt::register_godot_group) to [`App`].
+pub trait GodotGroupAppExt {
+    /// Keeps the component `C` on every entity whose [`ErasedGd`] node is in the Godot `group`.
+    ///
+    /// Group membership is checked every update, `C` is inserted when a node joins the group and
+    /// removed when it leaves, so `Query<&ErasedGd, With<C>>` matches the nodes of the group.
+    /// Markers inserted by other code are left alone.
+    ///
+    /// # Panics
+    /// If a group is already registered for `C`, only one group can be registered per component
+    /// type.
+    fn register_godot_group<C: Component + Default>(&mut self, group: &str) -> &mut Self;
+}
+
+impl GodotGroupAppExt for App {
+    fn register_godot_group<C: Component + Default>(&mut self, group: &str) -> &mut Self {
+        assert!(
+            !self.world().contains_resource::<GodotGroup<C>>(),
+            "a Godot group is already registered for {}",
+            std::any::type_name::<C>()
+        );
+
+        self.insert_resource(GodotGroup::<C> {
+            name: group.to_string(),
+            inserted: HashSet::new(),
+            marker: PhantomData,
+        })
+        .add_systems(PreUpdate, sync_godot_group::<C>)
+    }
+}
+
+#[derive(Resource)]
+struct GodotGroup<C: Component> {
+    name: String,
+    /// Entities `C` was inserted on by [`sync_godot_group`]
+    inserted: HashSet<Entity>,
+    marker: PhantomData<fn() -> C>,
+}
+
+fn sync_godot_group<C: Component + Default>(
+    mut commands: Commands,
+    mut group: ResMut<GodotGroup<C>>,
+    entities: Res<GodotNodeEntities>,
+    members: Query<(), (With<C>, With<ErasedGd>)>,
+    mut scene_tree: SceneTreeRef,
+) {
+    let in_group: HashSet<Entity> = scene_tree
+        .get()
+        .get_nodes_in_group(&group.name)
+        .iter_shared()
+        .filter_map(|node| entities.get(node.instance_id()))
+        .collect();
+
+    // forget markers that were removed or despawned since, and remove those of nodes that left
+    let mut inserted = std::mem::take(&mut group.inserted);
+    inserted.retain(|&entity| {
+        if !members.contains(entity) {
+            return false;
+        }
+
+        if !in_group.contains(&entity) {
+            commands.entity(entity).try_remove::<C>();
+            return false;
+        }
+
+        true
+    });
+
+    for &entity in in_group.iter() {
+        if !members.contains(entity) {
+            commands.entity(entity).try_insert(C::default());
+            inserted.insert(entity);
+        }
+    }
+
+    group.inserted = inserted;
+}
@@ -0,0 +1,461 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    ecs::schedule::IntoScheduleConfigs,
+    input::{
+        ButtonState, InputPlugin, InputSystem,
+        gamepad::{
+            GamepadAxis, GamepadButton, GamepadConnection, GamepadConnectionEvent,
+            RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent,
+        },
+        keyboard::{Key as BevyKey, KeyCode, KeyboardInput, NativeKey, NativeKeyCode},
+        mouse::{
+            MouseButton as BevyMouseButton, MouseButtonInput, MouseMotion, MouseScrollUnit,
+            MouseWheel,
+        },
+    },
+    math::Vec2,
+    platform::collections::HashMap,
+    prelude::{Commands, Entity, Event, EventReader, EventWriter, Local, Res, Resource},
+};
+use godot::{
+    classes::{
+        Input, InputEvent, InputEventJoypadButton, InputEventJoypadMotion, InputEventKey,
+        InputEventMouseButton, InputEventMouseMotion,
+    },
+    global::{JoyAxis, JoyButton, Key, KeyLocation, MouseButton},
+    obj::{EngineEnum, Gd},
+};
+use send_wrapper::SendWrapper;
+
+pub(crate) struct GodotInputEventPlugin;
+impl Plugin for GodotInputEventPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GodotInputEvent>();
+    }
+}
+
+/// The `BevyApp` callback an [`InputEvent`] was received through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GodotInputEventType {
+    /// `_input`, which receives every input event.
+    #[default]
+    Input,
+    /// `_unhandled_input`, which only receives events that were not consumed, e.g. by GUI nodes.
+    UnhandledInput,
+}
+
+/// A Godot [`InputEvent`] received by `BevyApp`, delivered as a Bevy [`Event`].
+#[derive(Debug, Event)]
+pub struct GodotInputEvent {
+    pub event_type: GodotInputEventType,
+    event: SendWrapper<Gd<InputEvent>>,
+}
+
+impl GodotInputEvent {
+    pub(crate) fn new(event_type: GodotInputEventType, event: Gd<InputEvent>) -> Self {
+        Self {
+            event_type,
+            event: SendWrapper::new(event),
+        }
+    }
+
+    /// The Godot input event.
+    ///
+    /// # Panics
+    /// Godot objects are not thread-safe, so this panics if called off the main thread. Read the
+    /// event from a system that also takes a [`SceneTreeRef`].
+    pub fn get(&self) -> Gd<InputEvent> {
+        (*self.event).clone()
+    }
+}
+
+/// Translates [`GodotInputEvent`]s into Bevy's input events, so that [`ButtonInput<KeyCode>`],
+/// [`ButtonInput<MouseButton>`], [`MouseMotion`], [`MouseWheel`] and [`Gamepad`]s work as they do
+/// in a regular Bevy app.
+///
+/// Adds Bevy's [`InputPlugin`] if it hasn't been added yet.
+///
+/// [`ButtonInput<KeyCode>`]: bevy::input::ButtonInput
+/// [`ButtonInput<MouseButton>`]: bevy::input::ButtonInput
+/// [`Gamepad`]: bevy::input::gamepad::Gamepad
+#[derive(Debug, Default)]
+pub struct GodotInputTranslationPlugin {
+    /// Which `BevyApp` callback to translate events from. Use
+    /// [`GodotInputEventType::UnhandledInput`] to ignore input consumed by Godot's GUI.
+    pub source: GodotInputEventType,
+}
+
+impl Plugin for GodotInputTranslationPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<InputPlugin>() {
+            app.add_plugins(InputPlugin);
+        }
+
+        app.insert_resource(InputTranslationSource(self.source))
+            .add_systems(PreUpdate, translate_input_events.before(InputSystem));
+    }
+}
+
+#[derive(Resource)]
+struct InputTranslationSource(GodotInputEventType);
+
+#[allow(clippy::too_many_arguments)]
+fn translate_input_events(
+    mut commands: Commands,
+    mut events: EventReader<GodotInputEvent>,
+    mut keyboard: EventWriter<KeyboardInput>,
+    mut mouse_buttons: EventWriter<MouseButtonInput>,
+    mut mouse_motion: EventWriter<MouseMotion>,
+    mut mouse_wheel: EventWriter<MouseWheel>,
+    mut gamepad_connections: EventWriter<GamepadConnectionEvent>,
+    mut gamepad_events: EventWriter<RawGamepadEvent>,
+    mut gamepads: Local<HashMap<i32, Entity>>,
+    source: Res<InputTranslationSource>,
+    _scene_tree: SceneTreeRef,
+) {
+    // Bevy gamepads are entities, keep one per connected Godot joypad device
+    let mut input = Input::singleton();
+    let connected = input.get_connected_joypads();
+
+    gamepads.retain(|device, gamepad| {
+        let is_connected = connected.contains(*device as i64);
+        if !is_connected {
+            gamepad_connections.write(GamepadConnectionEvent::new(
+                *gamepad,
+                GamepadConnection::Disconnected,
+            ));
+        }
+        is_connected
+    });
+
+    for device in connected.iter_shared() {
+        let device = device as i32;
+        if gamepads.contains_key(&device) {
+            continue;
+        }
+
+        let gamepad = commands.spawn_empty().id();
+        gamepads.insert(device, gamepad);
+        gamepad_connections.write(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected {
+                name: input.get_joy_name(device).to_string(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+    }
+
+    for event in events.read().filter(|event| event.event_type == source.0) {
+        let event = event.get();
+        // Bevy expects a window entity, Godot input isn't associated with one
+        let window = Entity::PLACEHOLDER;
+
+        if let Ok(key) = event.clone().try_cast::<InputEventKey>() {
+            let physical = match key.get_physical_keycode() {
+                Key::NONE => key.get_keycode(),
+                physical => physical,
+            };
+
+            keyboard.write(KeyboardInput {
+                key_code: key_code(physical, key.get_location()),
+                logical_key: BevyKey::Unidentified(NativeKey::Unidentified),
+                state: button_state(key.is_pressed()),
+                text: None,
+                repeat: key.is_echo(),
+                window,
+            });
+        } else if let Ok(button) = event.clone().try_cast::<InputEventMouseButton>() {
+            let factor = match button.get_factor() {
+                0.0 => 1.0,
+                factor => factor,
+            };
+            let wheel = |x, y| MouseWheel {
+                unit: MouseScrollUnit::Line,
+                x,
+                y,
+                window,
+            };
+
+            match button.get_button_index() {
+                MouseButton::WHEEL_UP
+                | MouseButton::WHEEL_DOWN
+                | MouseButton::WHEEL_LEFT
+                | MouseButton::WHEEL_RIGHT
+                    if !button.is_pressed() => {}
+                MouseButton::WHEEL_UP => {
+                    mouse_wheel.write(wheel(0.0, factor));
+                }
+                MouseButton::WHEEL_DOWN => {
+                    mouse_wheel.write(wheel(0.0, -factor));
+                }
+                MouseButton::WHEEL_LEFT => {
+                    mouse_wheel.write(wheel(-factor, 0.0));
+                }
+                MouseButton::WHEEL_RIGHT => {
+                    mouse_wheel.write(wheel(factor, 0.0));
+                }
+                index => {
+                    mouse_buttons.write(MouseButtonInput {
+                        button: mouse_button(index),
+                        state: button_state(button.is_pressed()),
+                        window,
+                    });
+                }
+            }
+        } else if let Ok(motion) = event.clone().try_cast::<InputEventMouseMotion>() {
+            let relative = motion.get_relative();
+            mouse_motion.write(MouseMotion {
+                delta: Vec2::new(relative.x, relative.y),
+            });
+        } else if let Ok(button) = event.clone().try_cast::<InputEventJoypadButton>() {
+            let Some(&gamepad) = gamepads.get(&button.get_device()) else {
+                continue;
+            };
+
+            gamepad_events.write(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+                gamepad,
+                gamepad_button(button.get_button_index()),
+                if button.is_pressed() { 1.0 } else { 0.0 },
+            )));
+        } else if let Ok(motion) = event.clone().try_cast::<InputEventJoypadMotion>() {
+            let Some(&gamepad) = gamepads.get(&motion.get_device()) else {
+                continue;
+            };
+
+            let value = motion.get_axis_value();
+            let button = |button| {
+                RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(gamepad, button, value))
+            };
+            let axis = |axis, value| {
+                RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(gamepad, axis, value))
+            };
+
+            gamepad_events.write(match motion.get_axis() {
+                // triggers are axes in Godot, but analog buttons in Bevy
+                JoyAxis::TRIGGER_LEFT => button(GamepadButton::LeftTrigger2),
+                JoyAxis::TRIGGER_RIGHT => button(GamepadButton::RightTrigger2),
+                // Godot's y axes point down, Bevy's point up
+                JoyAxis::LEFT_Y => axis(GamepadAxis::LeftStickY, -value),
+                JoyAxis::RIGHT_Y => axis(GamepadAxis::RightStickY, -value),
+                other => axis(gamepad_axis(other), value),
+            });
+        }
+    }
+}
+
+fn button_state(pressed: bool) -> ButtonState {
+    if pressed {
+        ButtonState::Pressed
+    } else {
+        ButtonState::Released
+    }
+}
+
+fn mouse_button(button: MouseButton) -> BevyMouseButton {
+    match button {
+        MouseButton::LEFT => BevyMouseButton::Left,
+        MouseButton::RIGHT => BevyMouseButton::Right,
+        MouseButton::MIDDLE => BevyMouseButton::Middle,
+        MouseButton::XBUTTON1 => BevyMouseButton::Back,
+        MouseButton::XBUTTON2 => BevyMouseButton::Forward,
+        other => BevyMouseButton::Other(other.ord() as u16),
+    }
+}
+
+fn gamepad_button(button: JoyButton) -> GamepadButton {
+    match button {
+        JoyButton::A => GamepadButton::South,
+        JoyButton::B => GamepadButton::East,
+        JoyButton::X => GamepadButton::West,
+        JoyButton::Y => GamepadButton::North,
+        JoyButton::BACK => GamepadButton::Select,
+        JoyButton::GUIDE => GamepadButton::Mode,
+        JoyButton::START => GamepadButton::Start,
+        JoyButton::LEFT_STICK => GamepadButton::LeftThumb,
+        JoyButton::RIGHT_STICK => GamepadButton::RightThumb,
+        JoyButton::LEFT_SHOULDER => GamepadButton::LeftTrigger,
+        JoyButton::RIGHT_SHOULDER => GamepadButton::RightTrigger,
+        JoyButton::DPAD_UP => GamepadButton::DPadUp,
+        JoyButton::DPAD_DOWN => GamepadButton::DPadDown,
+        JoyButton::DPAD_LEFT => GamepadButton::DPadLeft,
+        JoyButton::DPAD_RIGHT => GamepadButton::DPadRight,
+        other => GamepadButton::Other(other.ord() as u8),
+    }
+}
+
+fn gamepad_axis(axis: JoyAxis) -> GamepadAxis {
+    match axis {
+        JoyAxis::LEFT_X => GamepadAxis::LeftStickX,
+        JoyAxis::LEFT_Y => GamepadAxis::LeftStickY,
+        JoyAxis::RIGHT_X => GamepadAxis::RightStickX,
+        JoyAxis::RIGHT_Y => GamepadAxis::RightStickY,
+        other => GamepadAxis::Other(other.ord() as u8),
+    }
+}
+
+/// Maps a Godot physical key to a Bevy [`KeyCode`]
+///
+/// Godot doesn't distinguish left and right modifier keys in the keycode, so the key location
+/// is used for those.
+fn key_code(key: Key, location: KeyLocation) -> KeyCode {
+    let right = location == KeyLocation::RIGHT;
+
+    match key {
+        Key::ESCAPE => KeyCode::Escape,
+        Key::TAB | Key::BACKTAB => KeyCode::Tab,
+        Key::BACKSPACE => KeyCode::Backspace,
+        Key::ENTER => KeyCode::Enter,
+        Key::KP_ENTER => KeyCode::NumpadEnter,
+        Key::INSERT => KeyCode::Insert,
+        Key::DELETE => KeyCode::Delete,
+        Key::PAUSE => KeyCode::Pause,
+        Key::PRINT | Key::SYSREQ => KeyCode::PrintScreen,
+        Key::CLEAR => KeyCode::NumpadClear,
+        Key::HOME => KeyCode::Home,
+        Key::END => KeyCode::End,
+        Key::LEFT => KeyCode::ArrowLeft,
+        Key::UP => KeyCode::ArrowUp,
+        Key::RIGHT => KeyCode::ArrowRight,
+        Key::DOWN => KeyCode::ArrowDown,
+        Key::PAGEUP => KeyCode::PageUp,
+        Key::PAGEDOWN => KeyCode::PageDown,
+        Key::SHIFT if right => KeyCode::ShiftRight,
+        Key::SHIFT => KeyCode::ShiftLeft,
+        Key::CTRL if right => KeyCode::ControlRight,
+        Key::CTRL => KeyCode::ControlLeft,
+        Key::META if right => KeyCode::SuperRight,
+        Key::META => KeyCode::SuperLeft,
+        Key::ALT if right => KeyCode::AltRight,
+        Key::ALT => KeyCode::AltLeft,
+        Key::CAPSLOCK => KeyCode::CapsLock,
+        Key::NUMLOCK => KeyCode::NumLock,
+        Key::SCROLLLOCK => KeyCode::ScrollLock,
+        Key::F1 => KeyCode::F1,
+        Key::F2 => KeyCode::F2,
+        Key::F3 => KeyCode::F3,
+        Key::F4 => KeyCode::F4,
+        Key::F5 => KeyCode::F5,
+        Key::F6 => KeyCode::F6,
+        Key::F7 => KeyCode::F7,
+        Key::F8 => KeyCode::F8,
+        Key::F9 => KeyCode::F9,
+        Key::F10 => KeyCode::F10,
+        Key::F11 => KeyCode::F11,
+        Key::F12 => KeyCode::F12,
+        Key::F13 => KeyCode::F13,
+        Key::F14 => KeyCode::F14,
+        Key::F15 => KeyCode::F15,
+        Key::F16 => KeyCode::F16,
+        Key::F17 => KeyCode::F17,
+        Key::F18 => KeyCode::F18,
+        Key::F19 => KeyCode::F19,
+        Key::F20 => KeyCode::F20,
+        Key::F21 => KeyCode::F21,
+        Key::F22 => KeyCode::F22,
+        Key::F23 => KeyCode::F23,
+        Key::F24 => KeyCode::F24,
+        Key::F25 => KeyCode::F25,
+        Key::F26 => KeyCode::F26,
+        Key::F27 => KeyCode::F27,
+        Key::F28 => KeyCode::F28,
+        Key::F29 => KeyCode::F29,
+        Key::F30 => KeyCode::F30,
+        Key::F31 => KeyCode::F31,
+        Key::F32 => KeyCode::F32,
+        Key::F33 => KeyCode::F33,
+        Key::F34 => KeyCode::F34,
+        Key::F35 => KeyCode::F35,
+        Key::KP_MULTIPLY => KeyCode::NumpadMultiply,
+        Key::KP_DIVIDE => KeyCode::NumpadDivide,
+        Key::KP_SUBTRACT => KeyCode::NumpadSubtract,
+        Key::KP_PERIOD => KeyCode::NumpadDecimal,
+        Key::KP_ADD => KeyCode::NumpadAdd,
+        Key::KP_0 => KeyCode::Numpad0,
+        Key::KP_1 => KeyCode::Numpad1,
+        Key::KP_2 => KeyCode::Numpad2,
+        Key::KP_3 => KeyCode::Numpad3,
+        Key::KP_4 => KeyCode::Numpad4,
+        Key::KP_5 => KeyCode::Numpad5,
+        Key::KP_6 => KeyCode::Numpad6,
+        Key::KP_7 => KeyCode::Numpad7,
+        Key::KP_8 => KeyCode::Numpad8,
+        Key::KP_9 => KeyCode::Numpad9,
+        Key::MENU => KeyCode::ContextMenu,
+        Key::HYPER => KeyCode::Hyper,
+        Key::HELP => KeyCode::Help,
+        Key::BACK => KeyCode::BrowserBack,
+        Key::FORWARD => KeyCode::BrowserForward,
+        Key::STOP => KeyCode::BrowserStop,
+        Key::REFRESH => KeyCode::BrowserRefresh,
+        Key::VOLUMEDOWN => KeyCode::AudioVolumeDown,
+        Key::VOLUMEMUTE => KeyCode::AudioVolumeMute,
+        Key::VOLUMEUP => KeyCode::AudioVolumeUp,
+        Key::MEDIAPLAY => KeyCode::MediaPlayPause,
+        Key::MEDIASTOP => KeyCode::MediaStop,
+        Key::MEDIAPREVIOUS => KeyCode::MediaTrackPrevious,
+        Key::MEDIANEXT => KeyCode::MediaTrackNext,
+        Key::HOMEPAGE => KeyCode::BrowserHome,
+        Key::FAVORITES => KeyCode::BrowserFavorites,
+        Key::SEARCH => KeyCode::BrowserSearch,
+        Key::STANDBY => KeyCode::Sleep,
+        Key::LAUNCHMAIL => KeyCode::LaunchMail,
+        Key::LAUNCHMEDIA => KeyCode::MediaSelect,
+        Key::LAUNCH0 => KeyCode::LaunchApp1,
+        Key::LAUNCH1 => KeyCode::LaunchApp2,
+        Key::GLOBE => KeyCode::Fn,
+        Key::JIS_EISU => KeyCode::Lang2,
+        Key::JIS_KANA => KeyCode::Lang1,
+        Key::SPACE => KeyCode::Space,
+        Key::APOSTROPHE => KeyCode::Quote,
+        Key::COMMA => KeyCode::Comma,
+        Key::MINUS => KeyCode::Minus,
+        Key::PERIOD => KeyCode::Period,
+        Key::SLASH => KeyCode::Slash,
+        Key::KEY_0 => KeyCode::Digit0,
+        Key::KEY_1 => KeyCode::Digit1,
+        Key::KEY_2 => KeyCode::Digit2,
+        Key::KEY_3 => KeyCode::Digit3,
+        Key::KEY_4 => KeyCode::Digit4,
+        Key::KEY_5 => KeyCode::Digit5,
+        Key::KEY_6 => KeyCode::Digit6,
+        Key::KEY_7 => KeyCode::Digit7,
+        Key::KEY_8 => KeyCode::Digit8,
+        Key::KEY_9 => KeyCode::Digit9,
+        Key::SEMICOLON => KeyCode::Semicolon,
+        Key::EQUAL => KeyCode::Equal,
+        Key::A => KeyCode::KeyA,
+        Key::B => KeyCode::KeyB,
+        Key::C => KeyCode::KeyC,
+        Key::D => KeyCode::KeyD,
+        Key::E => KeyCode::KeyE,
+        Key::F => KeyCode::KeyF,
+        Key::G => KeyCode::KeyG,
+        Key::H => KeyCode::KeyH,
+        Key::I => KeyCode::KeyI,
+        Key::J => KeyCode::KeyJ,
+        Key::K => KeyCode::KeyK,
+        Key::L => KeyCode::KeyL,
+        Key::M => KeyCode::KeyM,
+        Key::N => KeyCode::KeyN,
+        Key::O => KeyCode::KeyO,
+        Key::P => KeyCode::KeyP,
+        Key::Q => KeyCode::KeyQ,
+        Key::R => KeyCode::KeyR,
+        Key::S => KeyCode::KeyS,
+        Key::T => KeyCode::KeyT,
+        Key::U => KeyCode::KeyU,
+        Key::V => KeyCode::KeyV,
+        Key::W => KeyCode::KeyW,
+        Key::X => KeyCode::KeyX,
+        Key::Y => KeyCode::KeyY,
+        Key::Z => KeyCode::KeyZ,
+        Key::BRACKETLEFT => KeyCode::BracketLeft,
+        Key::BACKSLASH => KeyCode::Backslash,
+        Key::BRACKETRIGHT => KeyCode::BracketRight,
+        Key::QUOTELEFT => KeyCode::Backquote,
+        Key::YEN => KeyCode::IntlYen,
+        _ => KeyCode::Unidentified(NativeKeyCode::Unidentified),
+    }
+}
@@ -1,18 +1,64 @@
 mod app;
 #[cfg(feature = "assets")]
 mod assets;
+mod entity_node;
 mod erased_gd;
+mod gdscript;
+mod groups;
+mod input_event;
+mod lifecycle;
+mod logging;
+mod node;
+mod node_registration;
+mod panic;
+mod pause;
+mod plugins;
+mod resource_collection;
 mod scene;
 mod scene_tree;
+mod signals;
+mod threaded_load;
+mod transform;
 mod utils;
+mod variant;
 
 pub mod prelude {
-    pub use super::erased_gd::{ErasedGd, ErasedGdResource};
-    pub use super::scene::GodotScene;
+    #[cfg(feature = "assets")]
+    pub use super::assets::GodotAssetReader;
+    pub use super::entity_node::BevyEntity;
+    pub use super::erased_gd::{ErasedGd, ErasedGdResource, GodotNodeEntities};
+    pub use super::gdscript::{GodotScriptAppExt, GodotScriptType};
+    pub use super::groups::GodotGroupAppExt;
+    pub use super::input_event::{
+        GodotInputEvent, GodotInputEventType, GodotInputTranslationPlugin,
+    };
+    pub use super::lifecycle::{GodotLifecycle, GodotNodeExitAction, GodotNodeExited};
+    pub use super::logging::{GodotLogLayer, godot_log_layer};
+    pub use super::node::{GodotNode, GodotNodeAppExt, GodotNodeQuery};
+    pub use super::node_registration::{
+        GodotClassName, GodotGroups, GodotNodeFilter, GodotNodeRegistrationPlugin,
+        RegisteredGodotNode,
+    };
+    pub use super::panic::{GodotPanic, GodotPanicHook, PanicPolicy};
+    pub use super::pause::{GodotPauseSettings, GodotPaused, run_when_paused, run_when_unpaused};
+    pub use super::plugins::GodotDefaultPlugins;
+    pub use super::resource_collection::{
+        GodotResourceCollection, GodotResourceLoadingPlugin, GodotResourceLoadingProgress,
+    };
+    pub use super::scene::{
+        GodotScene, GodotSceneFailed, GodotSceneLoading, GodotSceneParent, GodotSceneSettings,
+        GodotSceneSpawnError, GodotSceneSpawnFailed,
+    };
     pub use super::scene_tree::SceneTreeRef;
-    pub use super::utils::{
-        AsPhysicsSystem, AsVisualSystem, GodotPhysicsFrame, GodotVisualFrame, SystemDeltaTimer,
+    pub use super::signals::{GodotSignal, GodotSignals};
+    pub use super::transform::{GodotTransformSync, GodotTransformSyncPlugin};
+    #[allow(deprecated)]
+    pub use super::utils::{AsPhysicsSystem, AsVisualSystem};
+    pub use super::utils::{GodotPhysicsFrame, GodotVisualFrame, SystemDeltaTimer};
+    pub use super::variant::{
+        VariantConversionError, apply_variant, reflect_from_variant, reflect_to_variant,
+        variant_to_dynamic,
     };
-    pub use bevy_godot4_proc_macros::bevy_app;
+    pub use bevy_godot4_proc_macros::{GodotResourceCollection, GodotScriptType, bevy_app};
 }
-pub use app::{BevyApp, APP_BUILDER_FN};
+pub use app::{BevyApp, register_app, register_app_builder, register_named_app, unregister_apps};
@@ -0,0 +1,125 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    ecs::{component::HookContext, world::DeferredWorld},
+    prelude::{Commands, Component, Entity, EventReader, Query, With, Without},
+};
+use godot::{classes::Node, obj::Gd};
+
+pub(crate) struct GodotLifecyclePlugin;
+impl Plugin for GodotLifecyclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, (connect_tree_exiting, handle_tree_exiting));
+    }
+}
+
+/// Ties the lifetime of an entity to the lifetime of its [`ErasedGd`] node.
+///
+/// [`GodotScene`]s get the default lifecycle unless spawned with their own [`GodotLifecycle`].
+/// Insert it alongside any other [`ErasedGd`] to opt that entity in as well.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[component(on_despawn = free_node_on_despawn)]
+pub struct GodotLifecycle {
+    /// `queue_free` the node when the entity is despawned, unless the entity is despawned because
+    /// the node exited the tree.
+    pub free_node_on_despawn: bool,
+    /// What to do with the entity when the node exits the scene tree. Nodes that are only moved,
+    /// e.g. with `reparent()`, keep their entity.
+    pub on_tree_exiting: GodotNodeExitAction,
+}
+
+impl Default for GodotLifecycle {
+    fn default() -> Self {
+        Self {
+            free_node_on_despawn: true,
+            on_tree_exiting: GodotNodeExitAction::Despawn,
+        }
+    }
+}
+
+/// What happens to an entity when its node exits the scene tree, see [`GodotLifecycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GodotNodeExitAction {
+    /// Despawn the entity.
+    #[default]
+    Despawn,
+    /// Insert a [`GodotNodeExited`] marker on the entity.
+    Mark,
+    /// Leave the entity as is.
+    Ignore,
+}
+
+/// Marker inserted on entities whose node exited the scene tree, when their lifecycle is
+/// [`GodotNodeExitAction::Mark`].
+#[derive(Component, Debug, Default)]
+pub struct GodotNodeExited;
+
+#[derive(Component, Debug, Default)]
+struct TreeExitingConnected;
+
+fn free_node_on_despawn(world: DeferredWorld, context: HookContext) {
+    let entity = world.entity(context.entity);
+    let Some(lifecycle) = entity.get::<GodotLifecycle>() else {
+        return;
+    };
+
+    if !lifecycle.free_node_on_despawn {
+        return;
+    }
+
+    let node = entity
+        .get::<ErasedGd>()
+        .and_then(|gd| Gd::<Node>::try_from_instance_id(gd.instance_id()).ok());
+
+    if let Some(mut node) = node {
+        node.queue_free();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn connect_tree_exiting(
+    mut commands: Commands,
+    entities: Query<(Entity, &ErasedGd), (With<GodotLifecycle>, Without<TreeExitingConnected>)>,
+    signals: GodotSignals,
+) {
+    for (entity, gd) in entities.iter() {
+        signals.connect(entity, gd, "tree_exiting");
+        commands.entity(entity).insert(TreeExitingConnected);
+    }
+}
+
+fn handle_tree_exiting(
+    mut commands: Commands,
+    mut signals: EventReader<GodotSignal>,
+    mut lifecycles: Query<(&mut GodotLifecycle, &ErasedGd)>,
+    _scene_tree: SceneTreeRef,
+) {
+    for signal in signals.read() {
+        if signal.name != "tree_exiting" {
+            continue;
+        }
+
+        let Ok((mut lifecycle, gd)) = lifecycles.get_mut(signal.origin) else {
+            continue;
+        };
+
+        // reparented nodes exit the tree and enter it again, keep their entity
+        if Gd::<Node>::try_from_instance_id(gd.instance_id())
+            .is_ok_and(|node| node.is_inside_tree())
+        {
+            continue;
+        }
+
+        match lifecycle.on_tree_exiting {
+            GodotNodeExitAction::Despawn => {
+                // the node left on its own, whoever removed it owns it now
+                lifecycle.free_node_on_despawn = false;
+                commands.entity(signal.origin).try_despawn();
+            }
+            GodotNodeExitAction::Mark => {
+                commands.entity(signal.origin).try_insert(GodotNodeExited);
+            }
+            GodotNodeExitAction::Ignore => {}
+        }
+    }
+}
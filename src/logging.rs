@@ -0,0 +1,60 @@
+use bevy::{
+    app::App,
+    log::{
+        BoxedLayer, Level,
+        tracing::{
+            Event, Subscriber,
+            field::{Field, Visit},
+        },
+        tracing_subscriber::{Layer, layer::Context},
+    },
+};
+use godot::prelude::{godot_error, godot_print, godot_warn};
+use std::fmt::{self, Write};
+
+/// A tracing layer forwarding log events to Godot's output, so that they show up in the editor's
+/// Output dock: errors with `godot_error!`, warnings with `godot_warn!` and everything else with
+/// `godot_print!`.
+///
+/// [`GodotDefaultPlugins`](crate::prelude::GodotDefaultPlugins) adds it to its `LogPlugin`. When
+/// configuring your own `LogPlugin`, use [`godot_log_layer`] as its `custom_layer` to keep
+/// forwarding logs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GodotLogLayer;
+
+/// `LogPlugin::custom_layer` that adds a [`GodotLogLayer`].
+pub fn godot_log_layer(_app: &mut App) -> Option<BoxedLayer> {
+    Some(Box::new(GodotLogLayer))
+}
+
+impl<S: Subscriber> Layer<S> for GodotLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let message = format!("{}: {}", metadata.target(), visitor.message);
+        match *metadata.level() {
+            Level::ERROR => godot_error!("{message}"),
+            Level::WARN => godot_warn!("{message}"),
+            level => godot_print!("{level} {message}"),
+        }
+    }
+}
+
+/// Formats the `message` field followed by the other fields as `name=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{value:?}{fields}");
+        } else {
+            let _ = write!(self.message, " {}={value:?}", field.name());
+        }
+    }
+}
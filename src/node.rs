@@ -0,0 +1,183 @@
+use crate::prelude::*;
+use bevy::{
+    app::App,
+    ecs::{query::QueryFilter, system::SystemParam, world::DeferredWorld},
+    prelude::{Commands, Component, Entity, Query, Resource},
+};
+use godot::{
+    classes::Node,
+    obj::{Gd, Inherits, InstanceId},
+};
+use send_wrapper::SendWrapper;
+use std::{any::TypeId, fmt};
+
+/// A Godot node of class `T`, as a Bevy component.
+///
+/// Unlike [`ErasedGd`], the node is cast to `T` once when the component is created, so
+/// `Query<&GodotNode<T>>` only matches entities whose node is a `T`, and accessing the node doesn't
+/// cast it again. Use [`GodotNodeQuery`] to access the nodes from systems.
+///
+/// Register the class with [`GodotNodeAppExt::register_godot_node`] to insert this component on
+/// every entity whose [`ErasedGd`] node is a `T`, or create it yourself with
+/// [`GodotNode::try_from_erased`].
+///
+/// The node may only be accessed, and the component dropped, on the main thread.
+#[derive(Component)]
+pub struct GodotNode<T: Inherits<Node>> {
+    instance_id: InstanceId,
+    node: SendWrapper<Gd<T>>,
+}
+
+impl<T: Inherits<Node>> GodotNode<T> {
+    pub fn new(node: &Gd<T>) -> Self {
+        Self {
+            instance_id: node.instance_id(),
+            node: SendWrapper::new(node.clone()),
+        }
+    }
+
+    /// Returns `None` if the node no longer exists or isn't a `T`.
+    pub fn try_from_erased(gd: &ErasedGd) -> Option<Self> {
+        Gd::<T>::try_from_instance_id(gd.instance_id())
+            .ok()
+            .map(|node| Self::new(&node))
+    }
+
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance_id
+    }
+
+    /// Returns the node, or `None` if it has been freed.
+    ///
+    /// # Panics
+    /// If called from another thread than the main thread.
+    pub fn get(&self) -> Option<Gd<T>> {
+        let node: &Gd<T> = &self.node;
+        node.is_instance_valid().then(|| node.clone())
+    }
+}
+
+impl<T: Inherits<Node>> fmt::Debug for GodotNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GodotNode")
+            .field("class", &std::any::type_name::<T>())
+            .field("instance_id", &self.instance_id)
+            .finish()
+    }
+}
+
+/// Adds [`register_godot_node`](GodotNodeAppExt::register_godot_node) to [`App`].
+pub trait GodotNodeAppExt {
+    /// Inserts a [`GodotNode<T>`] on every entity that gets an [`ErasedGd`] whose node is a `T`,
+    /// right when the [`ErasedGd`] is inserted, and removes it along with the [`ErasedGd`].
+    fn register_godot_node<T: Inherits<Node>>(&mut self) -> &mut Self;
+}
+
+impl GodotNodeAppExt for App {
+    fn register_godot_node<T: Inherits<Node>>(&mut self) -> &mut Self {
+        let classes = &mut self
+            .init_resource::<GodotNodeClasses>()
+            .world_mut()
+            .resource_mut::<GodotNodeClasses>()
+            .0;
+
+        let class = GodotNodeClass {
+            type_id: TypeId::of::<T>(),
+            insert: insert_godot_node::<T>,
+            remove: remove_godot_node::<T>,
+        };
+        if !classes
+            .iter()
+            .any(|registered| registered.type_id == class.type_id)
+        {
+            classes.push(class);
+        }
+
+        self
+    }
+}
+
+/// Classes registered with [`GodotNodeAppExt::register_godot_node`], kept up to date by the
+/// hooks of [`ErasedGd`]
+#[derive(Resource, Default)]
+pub(crate) struct GodotNodeClasses(Vec<GodotNodeClass>);
+
+#[derive(Clone, Copy)]
+struct GodotNodeClass {
+    type_id: TypeId,
+    insert: fn(&mut Commands, Entity, InstanceId),
+    remove: fn(&mut Commands, Entity),
+}
+
+/// Queues inserting a [`GodotNode`] for every registered class the node is an instance of
+pub(crate) fn insert_godot_nodes(
+    world: &mut DeferredWorld,
+    entity: Entity,
+    instance_id: InstanceId,
+) {
+    let Some(classes) = world.get_resource::<GodotNodeClasses>() else {
+        return;
+    };
+
+    let classes = classes.0.clone();
+    let mut commands = world.commands();
+    for class in classes {
+        (class.insert)(&mut commands, entity, instance_id);
+    }
+}
+
+/// Queues removing the [`GodotNode`]s of all registered classes
+pub(crate) fn remove_godot_nodes(world: &mut DeferredWorld, entity: Entity) {
+    let Some(classes) = world.get_resource::<GodotNodeClasses>() else {
+        return;
+    };
+
+    let classes = classes.0.clone();
+    let mut commands = world.commands();
+    for class in classes {
+        (class.remove)(&mut commands, entity);
+    }
+}
+
+fn insert_godot_node<T: Inherits<Node>>(
+    commands: &mut Commands,
+    entity: Entity,
+    instance_id: InstanceId,
+) {
+    if let Ok(node) = Gd::<T>::try_from_instance_id(instance_id) {
+        commands.entity(entity).try_insert(GodotNode::new(&node));
+    }
+}
+
+fn remove_godot_node<T: Inherits<Node>>(commands: &mut Commands, entity: Entity) {
+    commands.entity(entity).try_remove::<GodotNode<T>>();
+}
+
+/// SystemParam to access the nodes of entities with a [`GodotNode<T>`].
+///
+/// Like [`SceneTreeRef`], this is a `NonSend` system param, so systems using it are scheduled on
+/// the main thread. Nodes that have been freed are skipped.
+#[derive(SystemParam)]
+pub struct GodotNodeQuery<'w, 's, T: Inherits<Node>, F: QueryFilter + 'static = ()> {
+    query: Query<'w, 's, (Entity, &'static GodotNode<T>), F>,
+    _scene_tree: SceneTreeRef<'w, 's>,
+}
+
+impl<T: Inherits<Node>, F: QueryFilter> GodotNodeQuery<'_, '_, T, F> {
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Gd<T>)> + '_ {
+        self.query
+            .iter()
+            .filter_map(|(entity, node)| node.get().map(|gd| (entity, gd)))
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<Gd<T>> {
+        let (_, node) = self.query.get(entity).ok()?;
+        node.get()
+    }
+
+    /// Returns the node if exactly one entity matches.
+    pub fn single(&self) -> Option<(Entity, Gd<T>)> {
+        let (entity, node) = self.query.single().ok()?;
+        node.get().map(|gd| (entity, gd))
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{prelude::*, signals::GodotSignalConnections};
+use bevy::{
+    app::{App, First, Plugin, PreStartup},
+    ecs::schedule::SystemSet,
+    log::tracing,
+    prelude::{
+        Commands, Component, IntoScheduleConfigs, Name, NonSend, Query, Res, Resource, With,
+    },
+};
+use godot::{
+    builtin::{Callable, StringName, Variant},
+    classes::Node,
+    global::Error,
+    obj::{Gd, GodotClass, Inherits, InstanceId},
+};
+use std::sync::{
+    Arc,
+    mpsc::{Receiver, Sender, channel},
+};
+
+/// Spawns an entity for every node in the scene tree that matches one of its [`GodotNodeFilter`]s,
+/// so that nodes placed in `.tscn` files by hand are queryable from Bevy.
+///
+/// Registered entities get an [`ErasedGd`], a [`Name`], a [`GodotClassName`], [`GodotGroups`]
+/// and a [`RegisteredGodotNode`] marker. They are despawned when their node leaves the scene
+/// tree. Nodes that already have an entity, e.g. instances of a [`GodotScene`], are skipped.
+#[derive(Default)]
+pub struct GodotNodeRegistrationPlugin {
+    filters: Vec<GodotNodeFilter>,
+}
+
+impl GodotNodeRegistrationPlugin {
+    /// Register nodes matching `filter`.
+    pub fn with_filter(mut self, filter: GodotNodeFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Register nodes in the given group.
+    pub fn with_group(self, group: &str) -> Self {
+        self.with_filter(GodotNodeFilter::Group(group.to_string()))
+    }
+
+    /// Register nodes of class `T`, including subclasses.
+    pub fn with_class<T: GodotClass + Inherits<Node>>(self) -> Self {
+        self.with_filter(GodotNodeFilter::Class(T::class_name().to_string()))
+    }
+}
+
+impl Plugin for GodotNodeRegistrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GodotNodeFilters(self.filters.clone()))
+            .init_non_send_resource::<SceneTreeChanges>()
+            .add_systems(PreStartup, connect_scene_tree)
+            .add_systems(First, update_registered_nodes.in_set(RegisterNodesSet));
+    }
+}
+
+/// Spawns the entities of registered nodes, in [`First`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RegisterNodesSet;
+
+/// Which scene tree nodes the [`GodotNodeRegistrationPlugin`] spawns entities for.
+#[derive(Clone)]
+pub enum GodotNodeFilter {
+    /// Every node in the scene tree.
+    All,
+    /// Nodes in the given group.
+    Group(String),
+    /// Nodes of the given class, including subclasses.
+    Class(String),
+    /// Nodes for which the function returns `true`.
+    Custom(Arc<GodotNodeFilterFn>),
+}
+
+pub type GodotNodeFilterFn = dyn Fn(&Gd<Node>) -> bool + Send + Sync;
+
+impl GodotNodeFilter {
+    pub(crate) fn matches(&self, node: &Gd<Node>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Group(group) => node.is_in_group(group),
+            Self::Class(class) => node.is_class(class),
+            Self::Custom(filter) => filter(node),
+        }
+    }
+}
+
+impl std::fmt::Debug for GodotNodeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "All"),
+            Self::Group(group) => f.debug_tuple("Group").field(group).finish(),
+            Self::Class(class) => f.debug_tuple("Class").field(class).finish(),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// The Godot class of a registered or mirrored node, e.g. `"CharacterBody2D"`.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct GodotClassName(pub String);
+
+/// The Godot groups a registered or mirrored node was in when its entity was spawned.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default)]
+pub struct GodotGroups(pub Vec<String>);
+
+impl GodotGroups {
+    pub fn contains(&self, group: &str) -> bool {
+        self.0.iter().any(|g| g == group)
+    }
+}
+
+/// Marker for entities spawned by the [`GodotNodeRegistrationPlugin`].
+#[derive(Component, Debug, Default)]
+pub struct RegisteredGodotNode;
+
+#[derive(Resource)]
+struct GodotNodeFilters(Vec<GodotNodeFilter>);
+
+impl GodotNodeFilters {
+    fn matches(&self, node: &Gd<Node>) -> bool {
+        self.0.iter().any(|filter| filter.matches(node))
+    }
+}
+
+enum SceneTreeChange {
+    Added(InstanceId),
+    Removed(InstanceId),
+}
+
+struct SceneTreeChanges {
+    sender: Sender<SceneTreeChange>,
+    receiver: Receiver<SceneTreeChange>,
+}
+
+impl Default for SceneTreeChanges {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+}
+
+fn connect_scene_tree(
+    changes: NonSend<SceneTreeChanges>,
+    connections: NonSend<GodotSignalConnections>,
+    mut scene_tree: SceneTreeRef,
+) {
+    let scene_tree = scene_tree.get();
+    for (signal, change) in [
+        (
+            "node_added",
+            SceneTreeChange::Added as fn(InstanceId) -> SceneTreeChange,
+        ),
+        ("node_removed", SceneTreeChange::Removed),
+    ] {
+        let sender = changes.sender.clone();
+        let callable = Callable::from_local_fn(signal, move |args| {
+            if let Some(node) = args.first().and_then(|arg| arg.try_to::<Gd<Node>>().ok()) {
+                let _ = sender.send(change(node.instance_id()));
+            }
+
+            Ok(Variant::nil())
+        });
+
+        let result = connections.connect(scene_tree.clone().upcast(), signal, callable);
+        if result != Error::OK {
+            tracing::error!("failed to connect SceneTree signal \"{signal}\": {result:?}");
+        }
+    }
+
+    // nodes that entered the tree before the app was built
+    let Some(root) = scene_tree.get_root() else {
+        return;
+    };
+
+    let nodes = root
+        .find_children_ex("*")
+        .recursive(true)
+        .owned(false)
+        .done();
+
+    for node in nodes.iter_shared() {
+        let _ = changes
+            .sender
+            .send(SceneTreeChange::Added(node.instance_id()));
+    }
+}
+
+fn update_registered_nodes(
+    mut commands: Commands,
+    changes: NonSend<SceneTreeChanges>,
+    filters: Res<GodotNodeFilters>,
+    entities: Res<GodotNodeEntities>,
+    registered: Query<(), With<RegisteredGodotNode>>,
+) {
+    let mut spawned: Vec<InstanceId> = Vec::new();
+
+    for change in changes.receiver.try_iter() {
+        match change {
+            SceneTreeChange::Added(instance_id) => {
+                if entities.get(instance_id).is_some() || spawned.contains(&instance_id) {
+                    continue;
+                }
+
+                let Ok(node) = Gd::<Node>::try_from_instance_id(instance_id) else {
+                    continue;
+                };
+
+                if !node.is_inside_tree() || !filters.matches(&node) {
+                    continue;
+                }
+
+                spawned.push(instance_id);
+                commands.spawn((node_components(node), RegisteredGodotNode));
+            }
+            SceneTreeChange::Removed(instance_id) => {
+                let Some(entity) = entities.get(instance_id) else {
+                    continue;
+                };
+
+                // reparented nodes are removed and added again, keep their entity
+                if Gd::<Node>::try_from_instance_id(instance_id)
+                    .is_ok_and(|node| node.is_inside_tree())
+                {
+                    continue;
+                }
+
+                if registered.contains(entity) {
+                    commands.entity(entity).try_despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Components describing a node, shared by registered nodes and mirrored scene hierarchies
+pub(crate) fn node_components(node: Gd<Node>) -> (ErasedGd, Name, GodotClassName, GodotGroups) {
+    let groups = node
+        .get_groups()
+        .iter_shared()
+        .map(|group: StringName| group.to_string())
+        // groups starting with an underscore are used internally by Godot
+        .filter(|group| !group.starts_with('_'))
+        .collect();
+
+    (
+        ErasedGd::new(node.clone()),
+        Name::new(node.get_name().to_string()),
+        GodotClassName(node.get_class().to_string()),
+        GodotGroups(groups),
+    )
+}
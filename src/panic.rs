@@ -0,0 +1,111 @@
+use bevy::prelude::Resource;
+use std::{
+    any::Any,
+    fmt,
+    panic::PanicHookInfo,
+    sync::{Arc, Mutex, Once},
+};
+
+/// What `BevyApp` does when updating the app panics.
+///
+/// Insert it in your `#[bevy_app]` function to change the default.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicPolicy {
+    /// Abort the process.
+    Crash,
+    /// Drop the app. The game keeps running without it.
+    #[default]
+    StopApp,
+    /// Drop the app and build a new one with the `#[bevy_app]` function. Nodes spawned by the
+    /// old app are left in the scene tree.
+    ///
+    /// An app can't keep running after a panic, the schedules that were running are lost when
+    /// the panic unwinds out of them.
+    RebuildApp,
+}
+
+/// A panic caught while updating the app, see [`GodotPanicHook`].
+#[derive(Debug, Clone)]
+pub struct GodotPanic {
+    pub message: String,
+    /// Source location of the panic, e.g. `src/lib.rs:12:5`.
+    pub location: Option<String>,
+    /// The system that returned an error, when the panic comes from Bevy's default error handler.
+    /// Bevy doesn't report which system panicked otherwise.
+    pub system: Option<String>,
+}
+
+impl GodotPanic {
+    pub(crate) fn new(payload: &(dyn Any + Send)) -> Self {
+        let message = payload_message(payload);
+        let location = LAST_PANIC
+            .lock()
+            .ok()
+            .and_then(|mut last| last.take())
+            .filter(|(last_message, _)| *last_message == message)
+            .map(|(_, location)| location);
+
+        let system = message
+            .strip_prefix("Encountered an error in system `")
+            .and_then(|rest| rest.split_once("`: "))
+            .map(|(system, _)| system.to_string());
+
+        Self {
+            message,
+            location,
+            system,
+        }
+    }
+}
+
+impl fmt::Display for GodotPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Called with every panic caught while updating the app, before the [`PanicPolicy`] is applied,
+/// e.g. to send crash reports.
+///
+/// Insert it in your `#[bevy_app]` function.
+#[derive(Resource, Clone)]
+pub struct GodotPanicHook(pub Arc<dyn Fn(&GodotPanic) + Send + Sync>);
+
+impl GodotPanicHook {
+    pub fn new(hook: impl Fn(&GodotPanic) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+}
+
+/// Message and location of the last panic, recorded by the panic hook
+static LAST_PANIC: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+/// Chains a panic hook recording the location of panics, which isn't part of the payload
+pub(crate) fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info: &PanicHookInfo| {
+            if let (Ok(mut last), Some(location)) = (LAST_PANIC.lock(), info.location()) {
+                *last = Some((payload_message(info.payload()), location.to_string()));
+            }
+
+            previous(info);
+        }));
+    });
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
@@ -0,0 +1,97 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, First, Last, Plugin},
+    prelude::{IntoScheduleConfigs, Res, ResMut, Resource},
+    time::{Time, TimeSystem, Virtual},
+};
+use godot::classes::node::ProcessMode;
+
+pub(crate) struct GodotPausePlugin;
+impl Plugin for GodotPausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GodotPaused>()
+            .init_resource::<GodotPauseSettings>()
+            .init_resource::<SyncedPause>()
+            .add_systems(First, read_scene_tree_pause.before(TimeSystem))
+            .add_systems(Last, write_scene_tree_pause);
+    }
+}
+
+/// Whether the scene tree is paused, see `SceneTree.paused`.
+///
+/// Updated at the start of every frame. Changing it pauses or unpauses the scene tree at the end
+/// of the frame. [`Time<Virtual>`] is paused along with the scene tree.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GodotPaused(pub bool);
+
+/// How the `BevyApp` node behaves while the scene tree is paused.
+///
+/// Insert it in your `#[bevy_app]` function to change the defaults.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GodotPauseSettings {
+    /// The `process_mode` to set on the `BevyApp` node when the app is built.
+    ///
+    /// Defaults to `None`, which leaves the node's own `process_mode` alone, so the app stops
+    /// updating while paused unless the node is set to process in the editor. Use
+    /// [`ProcessMode::ALWAYS`] to keep updating the app while paused, so that systems can use
+    /// [`run_when_paused`] and [`run_when_unpaused`].
+    ///
+    /// [`FixedMain`](bevy::app::FixedMain) doesn't run while paused with either mode.
+    pub process_mode: Option<ProcessMode>,
+}
+
+/// Run condition that is true while the scene tree is paused.
+pub fn run_when_paused(paused: Res<GodotPaused>) -> bool {
+    paused.0
+}
+
+/// Run condition that is true while the scene tree is not paused.
+pub fn run_when_unpaused(paused: Res<GodotPaused>) -> bool {
+    !paused.0
+}
+
+/// The pause flag of the scene tree as of the last sync
+#[derive(Resource, Debug, Default)]
+struct SyncedPause(bool);
+
+fn read_scene_tree_pause(
+    mut paused: ResMut<GodotPaused>,
+    mut synced: ResMut<SyncedPause>,
+    virtual_time: Option<ResMut<Time<Virtual>>>,
+    mut scene_tree: SceneTreeRef,
+) {
+    let tree_paused = scene_tree.get().is_paused();
+    synced.0 = tree_paused;
+
+    if paused.0 == tree_paused {
+        return;
+    }
+
+    paused.0 = tree_paused;
+    pause_virtual_time(virtual_time, tree_paused);
+}
+
+fn write_scene_tree_pause(
+    paused: Res<GodotPaused>,
+    mut synced: ResMut<SyncedPause>,
+    virtual_time: Option<ResMut<Time<Virtual>>>,
+    mut scene_tree: SceneTreeRef,
+) {
+    if paused.0 != synced.0 {
+        scene_tree.get().set_pause(paused.0);
+        synced.0 = paused.0;
+        pause_virtual_time(virtual_time, paused.0);
+    }
+}
+
+fn pause_virtual_time(virtual_time: Option<ResMut<Time<Virtual>>>, paused: bool) {
+    let Some(mut virtual_time) = virtual_time else {
+        return;
+    };
+
+    if paused {
+        virtual_time.pause();
+    } else {
+        virtual_time.unpause();
+    }
+}
@@ -0,0 +1,42 @@
+use crate::prelude::*;
+use bevy::{
+    app::{PluginGroup, PluginGroupBuilder, TaskPoolPlugin},
+    diagnostic::{DiagnosticsPlugin, FrameCountPlugin},
+    log::{LogPlugin, tracing},
+    time::TimePlugin,
+};
+
+/// The Bevy plugins a `BevyApp` needs to run, add them in your `#[bevy_app]` function.
+///
+/// - [`TaskPoolPlugin`]
+/// - [`LogPlugin`], forwarding logs to Godot with [`godot_log_layer`]. Left out when a global
+///   tracing subscriber is already set, e.g. by another app or an app rebuilt after a panic, whose
+///   subscriber keeps forwarding the logs
+/// - [`FrameCountPlugin`]
+/// - [`DiagnosticsPlugin`]
+/// - [`TimePlugin`], advanced by Godot's frame and physics deltas
+///
+/// Use [`PluginGroup::set`] to configure a plugin and [`PluginGroupBuilder::disable`] to leave it
+/// out, e.g. to use your own logging setup.
+pub struct GodotDefaultPlugins;
+
+impl PluginGroup for GodotDefaultPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(TaskPoolPlugin::default())
+            .add(LogPlugin {
+                custom_layer: godot_log_layer,
+                ..Default::default()
+            })
+            .add(FrameCountPlugin)
+            .add(DiagnosticsPlugin)
+            .add(TimePlugin);
+
+        // the global subscriber can only be set once per process
+        if tracing::dispatcher::has_been_set() {
+            group.disable::<LogPlugin>()
+        } else {
+            group
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    prelude::*,
+    threaded_load::{get_threaded_load, request_threaded_load, threaded_load_status},
+};
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::schedule::SystemSet,
+    log::tracing,
+    platform::collections::HashMap,
+    prelude::{
+        Commands, Condition, IntoScheduleConfigs, NextState, OnEnter, Res, ResMut, Resource,
+        in_state, resource_exists,
+    },
+    state::state::FreelyMutableState,
+};
+use godot::classes::resource_loader::ThreadLoadStatus;
+use std::marker::PhantomData;
+
+/// A Bevy [`Resource`] of Godot resources, loaded by the [`GodotResourceLoadingPlugin`].
+///
+/// Usually derived, with the path of every [`ErasedGdResource`] field in a
+/// `#[resource(path = "res://...")]` attribute. Fields without the attribute are initialized with
+/// [`Default`].
+pub trait GodotResourceCollection: Resource + Sized {
+    /// The paths of the resources to load.
+    fn resource_paths() -> &'static [&'static str];
+
+    /// Builds the collection from the loaded resources, in the order of
+    /// [`resource_paths`](Self::resource_paths).
+    fn from_resources(resources: Vec<ErasedGdResource>) -> Self;
+}
+
+/// Loads [`GodotResourceCollection`]s on background threads while the app is in
+/// `loading_state`, inserts them as resources, and transitions to `next_state` once all of them
+/// are loaded. Progress is tracked in [`GodotResourceLoadingProgress`].
+///
+/// Requires the `StatesPlugin` and the state to be initialized with `init_state`.
+pub struct GodotResourceLoadingPlugin<S: FreelyMutableState> {
+    loading_state: S,
+    next_state: S,
+    collections: Vec<fn(&mut App, S)>,
+}
+
+impl<S: FreelyMutableState> GodotResourceLoadingPlugin<S> {
+    pub fn new(loading_state: S, next_state: S) -> Self {
+        Self {
+            loading_state,
+            next_state,
+            collections: Vec::new(),
+        }
+    }
+
+    /// Load the collection `C` when entering the loading state.
+    pub fn load_collection<C: GodotResourceCollection>(mut self) -> Self {
+        self.collections.push(add_collection::<C, S>);
+        self
+    }
+}
+
+impl<S: FreelyMutableState> Plugin for GodotResourceLoadingPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GodotResourceLoadingProgress>()
+            .insert_resource(NextLoadingState(self.next_state.clone()))
+            .add_systems(
+                Update,
+                finish_loading::<S>
+                    .after(LoadCollectionsSet)
+                    .run_if(in_state(self.loading_state.clone())),
+            );
+
+        for add_collection in self.collections.iter() {
+            add_collection(app, self.loading_state.clone());
+        }
+    }
+}
+
+/// Progress of the [`GodotResourceLoadingPlugin`].
+#[derive(Resource, Debug, Default)]
+pub struct GodotResourceLoadingProgress {
+    progress: HashMap<String, f32>,
+    failed: Vec<String>,
+    pending_collections: usize,
+}
+
+impl GodotResourceLoadingProgress {
+    /// Average loading progress of all requested resources, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f32 {
+        if self.progress.is_empty() {
+            return 1.0;
+        }
+
+        self.progress.values().sum::<f32>() / self.progress.len() as f32
+    }
+
+    /// Paths of the resources that failed to load. Collections containing them are never inserted.
+    pub fn failed(&self) -> &[String] {
+        &self.failed
+    }
+}
+
+#[derive(Resource)]
+struct NextLoadingState<S: FreelyMutableState>(S);
+
+#[derive(Resource)]
+struct PendingCollection<C: GodotResourceCollection> {
+    marker: PhantomData<fn() -> C>,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct LoadCollectionsSet;
+
+fn add_collection<C: GodotResourceCollection, S: FreelyMutableState>(app: &mut App, state: S) {
+    app.add_systems(OnEnter(state.clone()), start_loading::<C>)
+        .add_systems(
+            Update,
+            load_collection::<C>
+                .in_set(LoadCollectionsSet)
+                .run_if(in_state(state).and(resource_exists::<PendingCollection<C>>)),
+        );
+}
+
+fn start_loading<C: GodotResourceCollection>(
+    mut commands: Commands,
+    mut progress: ResMut<GodotResourceLoadingProgress>,
+) {
+    for path in C::resource_paths() {
+        if request_threaded_load(path) {
+            progress.progress.insert(path.to_string(), 0.0);
+        } else {
+            tracing::error!("failed to request loading \"{path}\"");
+            progress.failed.push(path.to_string());
+        }
+    }
+
+    progress.pending_collections += 1;
+    commands.insert_resource(PendingCollection::<C> {
+        marker: PhantomData,
+    });
+}
+
+fn load_collection<C: GodotResourceCollection>(
+    mut commands: Commands,
+    mut progress: ResMut<GodotResourceLoadingProgress>,
+) {
+    let mut loaded = true;
+    for path in C::resource_paths() {
+        if progress.failed.iter().any(|failed| failed == path) {
+            loaded = false;
+            continue;
+        }
+
+        let (status, path_progress) = threaded_load_status(path);
+        match status {
+            ThreadLoadStatus::LOADED => {
+                progress.progress.insert(path.to_string(), 1.0);
+            }
+            ThreadLoadStatus::IN_PROGRESS => {
+                progress.progress.insert(path.to_string(), path_progress);
+                loaded = false;
+            }
+            _ => {
+                tracing::error!("failed to load \"{path}\"");
+                progress.failed.push(path.to_string());
+                loaded = false;
+            }
+        }
+    }
+
+    if !loaded {
+        return;
+    }
+
+    let mut resources = Vec::new();
+    for path in C::resource_paths() {
+        match get_threaded_load(path) {
+            Some(resource) => resources.push(ErasedGdResource::new(resource)),
+            None => {
+                tracing::error!("failed to get the loaded resource \"{path}\"");
+                progress.failed.push(path.to_string());
+            }
+        }
+    }
+
+    progress.pending_collections -= 1;
+    commands.remove_resource::<PendingCollection<C>>();
+    if resources.len() == C::resource_paths().len() {
+        commands.insert_resource(C::from_resources(resources));
+    }
+}
+
+fn finish_loading<S: FreelyMutableState>(
+    progress: Res<GodotResourceLoadingProgress>,
+    next: Res<NextLoadingState<S>>,
+    mut next_state: ResMut<NextState<S>>,
+) {
+    if progress.pending_collections == 0 && progress.failed.is_empty() {
+        next_state.set(next.0.clone());
+    }
+}
@@ -1,20 +1,77 @@
-use std::str::FromStr;
-
-use crate::prelude::*;
+use crate::{
+    app::BevyAppNode,
+    prelude::*,
+    threaded_load::{get_threaded_load, request_threaded_load, threaded_load_status},
+};
+#[cfg(feature = "assets")]
+use bevy::asset::{AssetServer, Assets, Handle, LoadState};
 use bevy::{
     app::{App, Plugin, PostUpdate},
+    ecs::{component::HookContext, world::DeferredWorld},
     log::tracing,
-    prelude::{Commands, Component, Entity, Query, Without},
+    prelude::{
+        ChildOf, Commands, Component, Entity, Event, EventWriter, Query, Res, ResMut, Resource,
+        Without,
+    },
 };
 use godot::{
-    builtin::{GString, Transform2D, Transform3D, Vector2, Vector3},
-    classes::{Node2D, Node3D, PackedScene, ResourceLoader},
+    builtin::{NodePath, Transform2D, Transform3D, Vector2, Vector3},
+    classes::{
+        Node, Node2D, Node3D, PackedScene, ResourceLoader, resource_loader::ThreadLoadStatus,
+    },
+    obj::Gd,
 };
 
 pub(crate) struct PackedScenePlugin;
 impl Plugin for PackedScenePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostUpdate, spawn_scene);
+        app.init_resource::<GodotSceneSettings>()
+            .init_resource::<AbandonedSceneLoads>()
+            .add_event::<GodotSceneSpawnFailed>()
+            .add_systems(PostUpdate, (spawn_scene, collect_abandoned_scene_loads));
+    }
+}
+
+/// App-wide settings for spawning [`GodotScene`]s.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GodotSceneSettings {
+    /// Path of the node instances are added to when their [`GodotScene`] has no parent of its
+    /// own, relative to the scene tree root. Defaults to `None`, the `BevyApp` node of the app.
+    pub default_parent: Option<String>,
+}
+
+/// Where the instance of a [`GodotScene`] is added to the scene tree, see
+/// [`GodotScene::with_parent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GodotSceneParent {
+    /// The [`ErasedGd`] node of an entity. The scene's entity becomes a child of this entity.
+    Entity(Entity),
+    /// A node path relative to the scene tree root, e.g. `"Main/Level"`, or an absolute path,
+    /// e.g. `"/root/Main/UI"`.
+    Path(String),
+}
+
+impl From<Entity> for GodotSceneParent {
+    fn from(entity: Entity) -> Self {
+        Self::Entity(entity)
+    }
+}
+
+impl From<&str> for GodotSceneParent {
+    fn from(path: &str) -> Self {
+        Self::Path(path.to_string())
+    }
+}
+
+impl From<String> for GodotSceneParent {
+    fn from(path: String) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<NodePath> for GodotSceneParent {
+    fn from(path: NodePath) -> Self {
+        Self::Path(path.to_string())
     }
 }
 
@@ -23,16 +80,27 @@ impl Plugin for PackedScenePlugin {
 /// [`GodotScene`]s that are spawned/inserted into the bevy world will be instanced from the provided
 /// handle/path and the instance will be added as an [`ErasedGd`] in the next PostUpdateFlush set.
 /// (see [`spawn_scene`])
+///
+/// If the scene can't be spawned, a [`GodotSceneSpawnFailed`] event is sent and the entity is
+/// marked with [`GodotSceneFailed`].
+///
+/// The instance is freed when the entity is despawned, and the entity is despawned when the
+/// instance exits the scene tree, unless the scene is spawned with a different [`GodotLifecycle`].
 #[derive(Debug, Component)]
+#[require(GodotLifecycle)]
+#[component(on_remove = abandon_scene_load)]
 pub struct GodotScene {
     resource: GodotSceneResource,
     transform: Option<GodotSceneTransform>,
+    parent: Option<GodotSceneParent>,
+    hierarchy: Option<GodotNodeFilter>,
 }
 
 #[derive(Debug)]
 enum GodotSceneResource {
     Resource(ErasedGdResource),
     Path(String),
+    ThreadedPath(String),
     #[cfg(feature = "assets")]
     Handle(Handle<ErasedGdResource>),
 }
@@ -44,33 +112,42 @@ enum GodotSceneTransform {
 }
 
 impl GodotScene {
-    /// Instantiate the godot scene from an ErasedGdResource.
-    pub fn from_resource(res: ErasedGdResource) -> Self {
+    fn new(resource: GodotSceneResource) -> Self {
         Self {
-            resource: GodotSceneResource::Resource(res),
+            resource,
             transform: None,
+            parent: None,
+            hierarchy: None,
         }
     }
 
+    /// Instantiate the godot scene from an ErasedGdResource.
+    pub fn from_resource(res: ErasedGdResource) -> Self {
+        Self::new(GodotSceneResource::Resource(res))
+    }
+
     /// Instantiate the godot scene from the given path.
     ///
     /// Note that this will call [`ResourceLoader`].load() - which is a blocking load.
     /// If you want "preload" functionality, you should load your resources into a Bevy [`Resource`]
-    /// and use from_resource().
+    /// and use from_resource(), or use from_path_threaded().
     pub fn from_path(path: &str) -> Self {
-        Self {
-            resource: GodotSceneResource::Path(path.to_string()),
-            transform: None,
-        }
+        Self::new(GodotSceneResource::Path(path.to_string()))
+    }
+
+    /// Instantiate the godot scene from the given path, loading it on a background thread.
+    ///
+    /// The scene is requested with `ResourceLoader.load_threaded_request()` and the entity gets a
+    /// [`GodotSceneLoading`] component tracking the progress until the scene is loaded and
+    /// instanced, which may take several frames.
+    pub fn from_path_threaded(path: &str) -> Self {
+        Self::new(GodotSceneResource::ThreadedPath(path.to_string()))
     }
 
     /// Instantiate the godot scene from a Bevy Asset [`Handle`].
     #[cfg(feature = "assets")]
     pub fn from_handle(handle: &Handle<ErasedGdResource>) -> Self {
-        Self {
-            resource: GodotSceneResource::Handle(handle.clone()),
-            transform: None,
-        }
+        Self::new(GodotSceneResource::Handle(handle.clone()))
     }
 
     pub fn with_transform3d(mut self, transform: Transform3D) -> Self {
@@ -96,50 +173,201 @@ impl GodotScene {
         ));
         self
     }
+
+    /// Add the instance as a child of `parent` instead of
+    /// [`GodotSceneSettings::default_parent`].
+    ///
+    /// `parent` is either an [`Entity`], in which case the instance is added to its [`ErasedGd`]
+    /// node and a [`ChildOf`] relationship to it is inserted, or a node path. If the parent entity
+    /// doesn't have its node yet, e.g. because it is a [`GodotScene`] spawned in the same frame,
+    /// spawning waits until it does.
+    pub fn with_parent(mut self, parent: impl Into<GodotSceneParent>) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    /// Spawn an entity for every node in the instanced scene, mirroring the node tree as a Bevy
+    /// [`ChildOf`]/[`Children`](bevy::prelude::Children) hierarchy under the scene's entity.
+    pub fn with_hierarchy(self) -> Self {
+        self.with_hierarchy_filter(GodotNodeFilter::All)
+    }
+
+    /// Like [`with_hierarchy`](Self::with_hierarchy), but only spawns entities for nodes matching
+    /// `filter`. Entities of matching nodes are parented to the entity of their closest matching
+    /// ancestor.
+    pub fn with_hierarchy_filter(mut self, filter: GodotNodeFilter) -> Self {
+        self.hierarchy = Some(filter);
+        self
+    }
 }
 
 #[derive(Component, Debug, Default)]
 struct GodotSceneSpawned;
 
+/// Why a [`GodotScene`] could not be spawned, see [`GodotSceneSpawnFailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GodotSceneSpawnError {
+    /// The resource at the given path could not be loaded.
+    LoadFailed(String),
+    /// The resource is not a `PackedScene`.
+    NotAPackedScene,
+    /// `PackedScene::instantiate` returned nothing.
+    InstantiateFailed,
+    /// The parent entity has no node, or no node exists at the parent path.
+    ParentMissing(GodotSceneParent),
+    /// The scene has no parent, and the `BevyApp` node it is added to by default was freed.
+    AppNodeMissing,
+}
+
+impl std::fmt::Display for GodotSceneSpawnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LoadFailed(path) => write!(f, "failed to load \"{path}\""),
+            Self::NotAPackedScene => write!(f, "resource is not a PackedScene"),
+            Self::InstantiateFailed => write!(f, "failed to instantiate the PackedScene"),
+            Self::ParentMissing(GodotSceneParent::Entity(entity)) => {
+                write!(
+                    f,
+                    "parent entity {entity} doesn't exist or its node was freed"
+                )
+            }
+            Self::ParentMissing(GodotSceneParent::Path(path)) => {
+                write!(f, "parent node \"{path}\" wasn't found")
+            }
+            Self::AppNodeMissing => write!(f, "the BevyApp node to add the scene to was freed"),
+        }
+    }
+}
+
+impl std::error::Error for GodotSceneSpawnError {}
+
+/// Sent when a [`GodotScene`] could not be spawned. The entity keeps its [`GodotScene`] and gets
+/// a [`GodotSceneFailed`] marker, it is not retried.
+#[derive(Event, Debug, Clone)]
+pub struct GodotSceneSpawnFailed {
+    pub entity: Entity,
+    pub reason: GodotSceneSpawnError,
+}
+
+/// Inserted on entities whose [`GodotScene`] is being loaded on a background thread, see
+/// [`GodotScene::from_path_threaded`]. Removed once the scene is spawned or failed to load.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct GodotSceneLoading {
+    /// Loading progress, from `0.0` to `1.0`.
+    pub progress: f32,
+}
+
+/// Marker inserted on entities whose [`GodotScene`] could not be spawned.
+#[derive(Component, Debug, Clone)]
+pub struct GodotSceneFailed(pub GodotSceneSpawnError);
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn spawn_scene(
     mut commands: Commands,
-    mut new_scenes: Query<(&mut GodotScene, Entity), Without<GodotSceneSpawned>>,
+    mut new_scenes: Query<
+        (&mut GodotScene, Entity, Option<&mut GodotSceneLoading>),
+        (Without<GodotSceneSpawned>, Without<GodotSceneFailed>),
+    >,
+    parents: Query<Option<&ErasedGd>>,
+    settings: Res<GodotSceneSettings>,
+    app_node: Res<BevyAppNode>,
+    mut failures: EventWriter<GodotSceneSpawnFailed>,
     #[cfg(feature = "assets")] mut assets: ResMut<Assets<ErasedGdResource>>,
+    #[cfg(feature = "assets")] asset_server: Res<AssetServer>,
     mut scene_tree: SceneTreeRef,
 ) {
-    for (mut scene, ent) in new_scenes.iter_mut() {
+    for (mut scene, ent, loading) in new_scenes.iter_mut() {
+        let Some(mut parent) =
+            (match find_parent(&scene, &parents, &settings, &app_node, &mut scene_tree) {
+                Ok(parent) => parent,
+                Err(reason) => {
+                    fail_spawn(&mut commands, &mut failures, ent, reason);
+                    continue;
+                }
+            })
+        else {
+            // the parent's node hasn't been spawned yet, try again next frame
+            continue;
+        };
+
         let packed_scene = match &mut scene.resource {
             GodotSceneResource::Resource(res) => res.get(),
-            GodotSceneResource::Path(path) => ResourceLoader::singleton()
-                .load(&GString::from_str(path).expect("path to be a valid GString"))
-                .expect("packed scene to load"),
+            GodotSceneResource::Path(path) => match ResourceLoader::singleton().load(path.as_str())
+            {
+                Some(resource) => resource,
+                None => {
+                    let reason = GodotSceneSpawnError::LoadFailed(path.clone());
+                    fail_spawn(&mut commands, &mut failures, ent, reason);
+                    continue;
+                }
+            },
+            GodotSceneResource::ThreadedPath(path) => match loading {
+                None => {
+                    if request_threaded_load(path) {
+                        commands.entity(ent).insert(GodotSceneLoading::default());
+                    } else {
+                        let reason = GodotSceneSpawnError::LoadFailed(path.clone());
+                        fail_spawn(&mut commands, &mut failures, ent, reason);
+                    }
+                    continue;
+                }
+                Some(mut loading) => match threaded_load_status(path) {
+                    (ThreadLoadStatus::IN_PROGRESS, progress) => {
+                        loading.progress = progress;
+                        continue;
+                    }
+                    (ThreadLoadStatus::LOADED, _) => match get_threaded_load(path) {
+                        Some(resource) => resource,
+                        None => {
+                            let reason = GodotSceneSpawnError::LoadFailed(path.clone());
+                            fail_spawn(&mut commands, &mut failures, ent, reason);
+                            continue;
+                        }
+                    },
+                    _ => {
+                        let reason = GodotSceneSpawnError::LoadFailed(path.clone());
+                        fail_spawn(&mut commands, &mut failures, ent, reason);
+                        continue;
+                    }
+                },
+            },
             #[cfg(feature = "assets")]
-            GodotSceneResource::Handle(handle) => assets
-                .get_mut(&handle)
-                .expect("packed scene to exist in assets")
-                .get(),
+            GodotSceneResource::Handle(handle) => match assets.get_mut(&*handle) {
+                Some(resource) => resource.get(),
+                // still loading, try again next frame
+                None if matches!(asset_server.load_state(handle.id()), LoadState::Loading) => {
+                    continue;
+                }
+                None => {
+                    let path = handle.path().map(|path| path.to_string());
+                    let reason = GodotSceneSpawnError::LoadFailed(path.unwrap_or_default());
+                    fail_spawn(&mut commands, &mut failures, ent, reason);
+                    continue;
+                }
+            },
         };
 
-        let instance = packed_scene
-            .try_cast::<PackedScene>()
-            .expect("resource to be a packed scene")
-            .instantiate()
-            .unwrap();
-
-        match scene_tree
-            .get()
-            .get_root()
-            .unwrap()
-            .get_node_or_null("BevyAppSingleton")
-        {
-            Some(mut app) => app.add_child(&instance),
-            None => {
-                tracing::error!(
-                    "attempted to add a child to the BevyAppSingleton autoload, but the BevyAppSingleton autoload wasn't found"
-                );
-                return;
-            }
-        }
+        let Ok(packed_scene) = packed_scene.try_cast::<PackedScene>() else {
+            fail_spawn(
+                &mut commands,
+                &mut failures,
+                ent,
+                GodotSceneSpawnError::NotAPackedScene,
+            );
+            continue;
+        };
+
+        let Some(instance) = packed_scene.instantiate() else {
+            fail_spawn(
+                &mut commands,
+                &mut failures,
+                ent,
+                GodotSceneSpawnError::InstantiateFailed,
+            );
+            continue;
+        };
+
+        parent.add_child(&instance);
 
         if let Some(transform) = &scene.transform {
             match transform {
@@ -162,9 +390,138 @@ fn spawn_scene(
             }
         }
 
+        if let Some(filter) = &scene.hierarchy {
+            spawn_hierarchy(&mut commands, &instance, ent, filter);
+        }
+
+        if let Some(GodotSceneParent::Entity(parent)) = &scene.parent {
+            commands.entity(ent).insert(ChildOf(*parent));
+        }
+
         commands
             .entity(ent)
             .insert(ErasedGd::new(instance))
-            .insert(GodotSceneSpawned);
+            .insert(GodotSceneSpawned)
+            .remove::<GodotSceneLoading>();
+    }
+}
+
+fn fail_spawn(
+    commands: &mut Commands,
+    failures: &mut EventWriter<GodotSceneSpawnFailed>,
+    entity: Entity,
+    reason: GodotSceneSpawnError,
+) {
+    tracing::error!("failed to spawn the GodotScene of {entity}: {reason}");
+    commands
+        .entity(entity)
+        .insert(GodotSceneFailed(reason.clone()))
+        .remove::<GodotSceneLoading>();
+    failures.write(GodotSceneSpawnFailed { entity, reason });
+}
+
+/// Paths of threaded loads whose [`GodotScene`] was removed before they finished. The loaded
+/// resources are still collected, otherwise `ResourceLoader` keeps them.
+#[derive(Resource, Default)]
+struct AbandonedSceneLoads(Vec<String>);
+
+fn abandon_scene_load(mut world: DeferredWorld, context: HookContext) {
+    let entity = world.entity(context.entity);
+    if !entity.contains::<GodotSceneLoading>() {
+        return;
+    }
+
+    let Some(GodotSceneResource::ThreadedPath(path)) =
+        entity.get::<GodotScene>().map(|scene| &scene.resource)
+    else {
+        return;
+    };
+
+    let path = path.clone();
+    if let Some(mut abandoned) = world.get_resource_mut::<AbandonedSceneLoads>() {
+        abandoned.0.push(path);
+    }
+}
+
+fn collect_abandoned_scene_loads(
+    mut abandoned: ResMut<AbandonedSceneLoads>,
+    _scene_tree: SceneTreeRef,
+) {
+    if abandoned.0.is_empty() {
+        return;
+    }
+
+    abandoned
+        .0
+        .retain(|path| match threaded_load_status(path).0 {
+            ThreadLoadStatus::IN_PROGRESS => true,
+            ThreadLoadStatus::LOADED => {
+                get_threaded_load(path);
+                false
+            }
+            // already collected, or failed
+            _ => false,
+        });
+}
+
+/// Returns `Ok(None)` if the parent entity doesn't have a node yet
+fn find_parent(
+    scene: &GodotScene,
+    parents: &Query<Option<&ErasedGd>>,
+    settings: &GodotSceneSettings,
+    app_node: &BevyAppNode,
+    scene_tree: &mut SceneTreeRef,
+) -> Result<Option<Gd<Node>>, GodotSceneSpawnError> {
+    let Some(parent) = scene
+        .parent
+        .clone()
+        .or_else(|| settings.default_parent.clone().map(GodotSceneParent::Path))
+    else {
+        return Gd::<Node>::try_from_instance_id(app_node.instance_id)
+            .map(Some)
+            .map_err(|_| GodotSceneSpawnError::AppNodeMissing);
+    };
+
+    let node = match &parent {
+        GodotSceneParent::Entity(entity) => match parents.get(*entity) {
+            Ok(Some(gd)) => Gd::<Node>::try_from_instance_id(gd.instance_id()).ok(),
+            Ok(None) => return Ok(None),
+            Err(_) => None,
+        },
+        GodotSceneParent::Path(path) => find_node(scene_tree, path),
+    };
+
+    node.map(Some)
+        .ok_or(GodotSceneSpawnError::ParentMissing(parent))
+}
+
+fn find_node(scene_tree: &mut SceneTreeRef, path: &str) -> Option<Gd<Node>> {
+    scene_tree
+        .get()
+        .get_root()?
+        .get_node_or_null(&NodePath::from(path))
+}
+
+/// Spawns entities for the descendants of `node` that match `filter`, parented to `parent`
+fn spawn_hierarchy(
+    commands: &mut Commands,
+    node: &Gd<Node>,
+    parent: Entity,
+    filter: &GodotNodeFilter,
+) {
+    for child in node.get_children().iter_shared() {
+        let child_parent = if filter.matches(&child) {
+            commands
+                .spawn((
+                    crate::node_registration::node_components(child.clone()),
+                    GodotLifecycle::default(),
+                    ChildOf(parent),
+                ))
+                .id()
+        } else {
+            parent
+        };
+
+        spawn_hierarchy(commands, &child, child_parent, filter);
     }
 }
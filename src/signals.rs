@@ -0,0 +1,160 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, First, Plugin},
+    ecs::system::SystemParam,
+    log::tracing,
+    prelude::{Entity, Event, EventWriter, NonSend},
+};
+use godot::{
+    builtin::{Callable, StringName, Variant},
+    classes::Object,
+    global::Error,
+    obj::{Gd, InstanceId},
+};
+use send_wrapper::SendWrapper;
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::mpsc::{Receiver, Sender, channel},
+};
+
+pub(crate) struct GodotSignalsPlugin;
+impl Plugin for GodotSignalsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GodotSignal>()
+            .init_non_send_resource::<GodotSignalsChannel>()
+            .init_non_send_resource::<GodotSignalConnections>()
+            .add_systems(First, write_godot_signal_events);
+    }
+}
+
+/// A Godot signal emission, delivered as a Bevy [`Event`].
+///
+/// Signals are connected with [`GodotSignals::connect`], and are written into the world at the
+/// start of the next Bevy update.
+#[derive(Debug, Event)]
+pub struct GodotSignal {
+    /// The entity whose node emitted the signal.
+    pub origin: Entity,
+    /// The name of the emitted signal, e.g. `"pressed"`.
+    pub name: String,
+    arguments: SendWrapper<Vec<Variant>>,
+}
+
+impl GodotSignal {
+    /// The arguments the signal was emitted with.
+    ///
+    /// # Panics
+    /// [`Variant`]s are not thread-safe, so this panics if called off the main thread. Read the
+    /// arguments from a system that also takes a [`SceneTreeRef`].
+    pub fn arguments(&self) -> &[Variant] {
+        &self.arguments
+    }
+}
+
+#[doc(hidden)]
+pub(crate) struct GodotSignalsChannel {
+    sender: Sender<GodotSignal>,
+    receiver: Receiver<GodotSignal>,
+}
+
+impl Default for GodotSignalsChannel {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self { sender, receiver }
+    }
+}
+
+/// Signals connected by the app, disconnected when the app is dropped so that stopped or rebuilt
+/// apps don't leave their callables connected
+#[derive(Default)]
+pub(crate) struct GodotSignalConnections {
+    connections: RefCell<Vec<(InstanceId, StringName, Callable)>>,
+}
+
+impl GodotSignalConnections {
+    /// Connects `callable` to `signal` of `object`, to be disconnected along with the app
+    pub(crate) fn connect(
+        &self,
+        mut object: Gd<Object>,
+        signal: &str,
+        callable: Callable,
+    ) -> Error {
+        let result = object.connect(signal, &callable);
+        if result != Error::OK {
+            return result;
+        }
+
+        let mut connections = self.connections.borrow_mut();
+        // forget the connections of freed objects before growing
+        if connections.len() == connections.capacity() {
+            connections.retain(|(instance_id, _, _)| {
+                Gd::<Object>::try_from_instance_id(*instance_id).is_ok()
+            });
+        }
+        connections.push((object.instance_id(), StringName::from(signal), callable));
+        result
+    }
+}
+
+impl Drop for GodotSignalConnections {
+    fn drop(&mut self) {
+        for (instance_id, signal, callable) in self.connections.get_mut().drain(..) {
+            let Ok(mut object) = Gd::<Object>::try_from_instance_id(instance_id) else {
+                continue;
+            };
+
+            if object.is_connected(&signal, &callable) {
+                object.disconnect(&signal, &callable);
+            }
+        }
+    }
+}
+
+/// SystemParam to connect Godot signals to [`GodotSignal`] events.
+///
+/// Like [`SceneTreeRef`], this is a `NonSend` system param, so systems using it are scheduled on
+/// the main thread.
+#[derive(SystemParam)]
+pub struct GodotSignals<'w, 's> {
+    channel: NonSend<'w, GodotSignalsChannel>,
+    connections: NonSend<'w, GodotSignalConnections>,
+    phantom: PhantomData<&'s ()>,
+}
+
+impl GodotSignals<'_, '_> {
+    /// Connect `signal_name` on the node held by `node`, so that each emission is written as a
+    /// [`GodotSignal`] event with `entity` as its origin.
+    pub fn connect(&self, entity: Entity, node: &ErasedGd, signal_name: &str) {
+        let Ok(object) = Gd::<Object>::try_from_instance_id(node.instance_id()) else {
+            tracing::error!(
+                "attempted to connect signal \"{signal_name}\" on {entity}, but its node no longer exists"
+            );
+            return;
+        };
+
+        let sender = self.channel.sender.clone();
+        let name = signal_name.to_string();
+        let callable = Callable::from_local_fn(signal_name, move |args| {
+            let _ = sender.send(GodotSignal {
+                origin: entity,
+                name: name.clone(),
+                arguments: SendWrapper::new(args.iter().map(|&arg| arg.clone()).collect()),
+            });
+
+            Ok(Variant::nil())
+        });
+
+        let result = self.connections.connect(object, signal_name, callable);
+        if result != Error::OK {
+            tracing::error!("failed to connect signal \"{signal_name}\" on {entity}: {result:?}");
+        }
+    }
+}
+
+fn write_godot_signal_events(
+    channel: NonSend<GodotSignalsChannel>,
+    mut events: EventWriter<GodotSignal>,
+) {
+    events.write_batch(channel.receiver.try_iter());
+}
@@ -0,0 +1,41 @@
+use godot::{
+    builtin::VariantArray,
+    classes::{ResourceLoader, resource_loader::ThreadLoadStatus},
+    global::Error,
+    meta::ToGodot,
+    obj::Gd,
+};
+
+// The threaded loading methods are only generated with gdext's `experimental-threads` feature,
+// so they are called dynamically instead.
+pub(crate) fn request_threaded_load(path: &str) -> bool {
+    let result = ResourceLoader::singleton().call("load_threaded_request", &[path.to_variant()]);
+    result
+        .try_to::<Error>()
+        .is_ok_and(|error| error == Error::OK)
+}
+
+pub(crate) fn threaded_load_status(path: &str) -> (ThreadLoadStatus, f32) {
+    let progress = VariantArray::new();
+    let status = ResourceLoader::singleton()
+        .call(
+            "load_threaded_get_status",
+            &[path.to_variant(), progress.to_variant()],
+        )
+        .try_to::<ThreadLoadStatus>()
+        .unwrap_or(ThreadLoadStatus::INVALID_RESOURCE);
+
+    let progress = progress
+        .get(0)
+        .and_then(|progress| progress.try_to::<f32>().ok())
+        .unwrap_or_default();
+
+    (status, progress)
+}
+
+pub(crate) fn get_threaded_load(path: &str) -> Option<Gd<godot::classes::Resource>> {
+    ResourceLoader::singleton()
+        .call("load_threaded_get", &[path.to_variant()])
+        .try_to::<Gd<godot::classes::Resource>>()
+        .ok()
+}
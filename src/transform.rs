@@ -0,0 +1,370 @@
+use crate::prelude::*;
+use bevy::{
+    app::{App, Plugin, PostUpdate, PreUpdate},
+    ecs::{component::Tick, schedule::IntoScheduleConfigs, system::SystemChangeTick},
+    math::{Affine2, Affine3A, EulerRot, Mat3, Quat, Vec2, Vec3},
+    prelude::{
+        ChildOf, Component, DetectChanges, DetectChangesMut, Entity, GlobalTransform, Query, Ref,
+        Res, ResMut, Resource, Transform,
+    },
+    transform::{TransformPlugin, TransformSystem},
+};
+use godot::{
+    builtin::{Transform2D, Transform3D, Vector2, Vector3},
+    classes::{Node, Node2D, Node3D},
+    obj::{Gd, Inherits},
+};
+
+/// Keeps Bevy [`Transform`]s and Godot `Node2D`/`Node3D` transforms in sync for entities with a
+/// [`GodotTransformSync`] component.
+///
+/// Godot transforms are read into [`Transform`] and [`GlobalTransform`] in `PreUpdate`, and
+/// [`Transform`]s changed by Bevy systems are written back in `PostUpdate`. The Bevy [`Transform`]
+/// holds the node's global transform, unless the entity has a [`ChildOf`] parent with a node of
+/// the same kind, in which case it holds the node's transform relative to that parent's node. The
+/// parent's node doesn't have to be the node's Godot parent. Under a [`ChildOf`] parent without
+/// such a node, it is relative to the parent's [`GlobalTransform`], so that propagation doesn't
+/// apply the parent twice.
+///
+/// 2D transforms are kept in Godot's 2D coordinate system (pixels, y pointing down). Their
+/// translation's `z` is left untouched.
+///
+/// Adds Bevy's [`TransformPlugin`] if it hasn't been added yet.
+pub struct GodotTransformSyncPlugin;
+impl Plugin for GodotTransformSyncPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TransformPlugin>() {
+            app.add_plugins(TransformPlugin);
+        }
+
+        app.init_resource::<LastGodotTransformSync>()
+            .add_systems(PreUpdate, godot_to_bevy_transforms)
+            .add_systems(
+                PostUpdate,
+                bevy_to_godot_transforms.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Which way the transform of an entity's Godot node and its Bevy [`Transform`] are synced by
+/// the [`GodotTransformSyncPlugin`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[require(Transform)]
+pub enum GodotTransformSync {
+    /// The Godot transform is copied into the Bevy transform every update.
+    GodotToBevy,
+    /// Changes to the Bevy transform are written to the Godot node.
+    BevyToGodot,
+    /// Both of the above. Bevy changes win over Godot changes made in the same frame.
+    #[default]
+    Bidirectional,
+}
+
+impl GodotTransformSync {
+    fn reads_godot(self) -> bool {
+        matches!(self, Self::GodotToBevy | Self::Bidirectional)
+    }
+
+    fn writes_godot(self) -> bool {
+        matches!(self, Self::BevyToGodot | Self::Bidirectional)
+    }
+}
+
+/// The tick transforms were last copied from Godot at, used to skip writing those same
+/// transforms back
+#[derive(Resource, Default)]
+struct LastGodotTransformSync(Tick);
+
+#[allow(clippy::type_complexity)]
+fn godot_to_bevy_transforms(
+    mut entities: Query<(
+        Entity,
+        &ErasedGd,
+        &GodotTransformSync,
+        &mut Transform,
+        Option<&ChildOf>,
+    )>,
+    mut global_transforms: Query<&mut GlobalTransform>,
+    parents: Query<&ErasedGd>,
+    mut last_sync: ResMut<LastGodotTransformSync>,
+    ticks: SystemChangeTick,
+    _scene_tree: SceneTreeRef,
+) {
+    for (entity, gd, sync, mut transform, child_of) in entities.iter_mut() {
+        if !sync.reads_godot() {
+            continue;
+        }
+
+        let Ok(node) = Gd::<Node>::try_from_instance_id(gd.instance_id()) else {
+            continue;
+        };
+
+        let parent_global =
+            child_of.and_then(|child_of| global_transforms.get(child_of.parent()).ok().copied());
+
+        let global = if let Ok(node) = node.clone().try_cast::<Node3D>() {
+            let node_global = node.get_global_transform();
+            let local = match parent_node::<Node3D>(child_of, &parents) {
+                Some(parent) => {
+                    transform_from_3d(parent.get_global_transform().affine_inverse() * node_global)
+                }
+                None => local_from_global(transform_from_3d(node_global), parent_global.as_ref()),
+            };
+
+            transform.set_if_neq(local);
+            transform_from_3d(node_global)
+        } else if let Ok(node) = node.try_cast::<Node2D>() {
+            let z = transform.translation.z;
+            let node_global = node.get_global_transform();
+            let mut local = match parent_node::<Node2D>(child_of, &parents) {
+                Some(parent) => transform_from_2d(
+                    parent.get_global_transform().affine_inverse() * node_global,
+                    z,
+                ),
+                None => {
+                    local_from_global(transform_from_2d(node_global, z), parent_global.as_ref())
+                }
+            };
+            local.translation.z = z;
+
+            transform.set_if_neq(local);
+            transform_from_2d(node_global, z)
+        } else {
+            continue;
+        };
+
+        if let Ok(mut global_transform) = global_transforms.get_mut(entity) {
+            global_transform.set_if_neq(global.into());
+        }
+    }
+
+    last_sync.0 = ticks.this_run();
+}
+
+fn bevy_to_godot_transforms(
+    entities: Query<(
+        &ErasedGd,
+        &GodotTransformSync,
+        Ref<Transform>,
+        Option<&ChildOf>,
+    )>,
+    parents: Query<&ErasedGd>,
+    global_transforms: Query<&GlobalTransform>,
+    last_sync: Res<LastGodotTransformSync>,
+    _scene_tree: SceneTreeRef,
+) {
+    for (gd, sync, transform, child_of) in entities.iter() {
+        // only write transforms that Bevy systems changed since they were read from Godot
+        if !sync.writes_godot()
+            || !transform.is_changed()
+            || transform.last_changed() == last_sync.0
+        {
+            continue;
+        }
+
+        let Ok(node) = Gd::<Node>::try_from_instance_id(gd.instance_id()) else {
+            continue;
+        };
+
+        let parent_global =
+            child_of.and_then(|child_of| global_transforms.get(child_of.parent()).ok());
+
+        if let Ok(mut node) = node.clone().try_cast::<Node3D>() {
+            let global = match parent_node::<Node3D>(child_of, &parents) {
+                Some(parent) => parent.get_global_transform() * transform_to_3d(&transform),
+                None => transform_to_3d(&global_from_local(&transform, parent_global)),
+            };
+            node.set_global_transform(global);
+        } else if let Ok(mut node) = node.try_cast::<Node2D>() {
+            let global = match parent_node::<Node2D>(child_of, &parents) {
+                Some(parent) => parent.get_global_transform() * transform_to_2d(&transform),
+                None => transform_to_2d(&global_from_local(&transform, parent_global)),
+            };
+            node.set_global_transform(global);
+        }
+    }
+}
+
+/// The node of the entity's Bevy parent, if it is a `T`
+fn parent_node<T: Inherits<Node>>(
+    child_of: Option<&ChildOf>,
+    parents: &Query<&ErasedGd>,
+) -> Option<Gd<T>> {
+    let parent = parents.get(child_of?.parent()).ok()?;
+    Gd::<Node>::try_from_instance_id(parent.instance_id())
+        .ok()?
+        .try_cast::<T>()
+        .ok()
+}
+
+/// The transform that `global` has under a Bevy parent without a node of the same kind, which
+/// propagation multiplies by `parent`
+fn local_from_global(global: Transform, parent: Option<&GlobalTransform>) -> Transform {
+    match parent {
+        Some(parent) => GlobalTransform::from(global).reparented_to(parent),
+        None => global,
+    }
+}
+
+/// The inverse of [`local_from_global`]
+fn global_from_local(local: &Transform, parent: Option<&GlobalTransform>) -> Transform {
+    match parent {
+        Some(parent) => parent.mul_transform(*local).compute_transform(),
+        None => *local,
+    }
+}
+
+fn transform_from_3d(transform: Transform3D) -> Transform {
+    let [a, b, c] = transform.basis.to_cols();
+    let affine = Affine3A::from_mat3_translation(
+        Mat3::from_cols(vec3(a), vec3(b), vec3(c)),
+        vec3(transform.origin),
+    );
+
+    Transform::from_matrix(affine.into())
+}
+
+fn transform_to_3d(transform: &Transform) -> Transform3D {
+    let affine = transform.compute_affine();
+
+    Transform3D::from_cols(
+        vector3(affine.matrix3.x_axis.into()),
+        vector3(affine.matrix3.y_axis.into()),
+        vector3(affine.matrix3.z_axis.into()),
+        vector3(affine.translation.into()),
+    )
+}
+
+fn transform_from_2d(transform: Transform2D, z: f32) -> Transform {
+    let affine = Affine2::from_cols(vec2(transform.a), vec2(transform.b), vec2(transform.origin));
+    let (scale, angle, translation) = affine.to_scale_angle_translation();
+
+    Transform {
+        translation: translation.extend(z),
+        rotation: Quat::from_rotation_z(angle),
+        scale: scale.extend(1.0),
+    }
+}
+
+fn transform_to_2d(transform: &Transform) -> Transform2D {
+    let (angle, _, _) = transform.rotation.to_euler(EulerRot::ZYX);
+    let affine = Affine2::from_scale_angle_translation(
+        transform.scale.truncate(),
+        angle,
+        transform.translation.truncate(),
+    );
+
+    Transform2D::from_cols(
+        vector2(affine.matrix2.x_axis),
+        vector2(affine.matrix2.y_axis),
+        vector2(affine.translation),
+    )
+}
+
+fn vec3(v: Vector3) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+fn vector3(v: Vec3) -> Vector3 {
+    Vector3::new(v.x, v.y, v.z)
+}
+
+fn vec2(v: Vector2) -> Vec2 {
+    Vec2::new(v.x, v.y)
+}
+
+fn vector2(v: Vec2) -> Vector2 {
+    Vector2::new(v.x, v.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    fn assert_transforms_eq(a: Transform, b: Transform) {
+        assert!(
+            a.translation.abs_diff_eq(b.translation, 1e-4)
+                && a.rotation.abs_diff_eq(b.rotation, 1e-4)
+                && a.scale.abs_diff_eq(b.scale, 1e-4),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn transform_3d_round_trip() {
+        let transform = Transform {
+            translation: Vec3::new(1.0, -2.0, 3.5),
+            rotation: Quat::from_euler(EulerRot::YXZ, 0.3, -1.2, FRAC_PI_4),
+            scale: Vec3::new(2.0, 0.5, 1.5),
+        };
+
+        assert_transforms_eq(transform_from_3d(transform_to_3d(&transform)), transform);
+    }
+
+    #[test]
+    fn transform_3d_matches_godot() {
+        let godot = Transform3D::IDENTITY
+            .rotated(Vector3::UP, FRAC_PI_4)
+            .translated(Vector3::new(1.0, 2.0, 3.0));
+        let transform = transform_from_3d(godot);
+
+        assert_transforms_eq(
+            transform,
+            Transform::from_xyz(1.0, 2.0, 3.0).with_rotation(Quat::from_rotation_y(FRAC_PI_4)),
+        );
+        assert_eq!(transform_to_3d(&transform).origin, godot.origin);
+    }
+
+    #[test]
+    fn transform_2d_round_trip() {
+        let transform = Transform {
+            translation: Vec3::new(100.0, -20.0, 7.0),
+            rotation: Quat::from_rotation_z(-1.1),
+            scale: Vec3::new(2.0, 3.0, 1.0),
+        };
+
+        assert_transforms_eq(
+            transform_from_2d(transform_to_2d(&transform), 7.0),
+            transform,
+        );
+    }
+
+    #[test]
+    fn transform_2d_matches_godot() {
+        let godot = Transform2D::from_angle_origin(FRAC_PI_4, Vector2::new(10.0, 20.0));
+        let transform = transform_from_2d(godot, 0.0);
+
+        assert_transforms_eq(
+            transform,
+            Transform::from_xyz(10.0, 20.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+        );
+        assert_eq!(transform_to_2d(&transform).origin, godot.origin);
+    }
+
+    #[test]
+    fn local_transform_relative_to_parent() {
+        let parent = Transform2D::from_angle_origin(FRAC_PI_4, Vector2::new(10.0, 0.0));
+        let local = Transform2D::from_angle_origin(0.0, Vector2::new(5.0, 0.0));
+        let global = parent * local;
+
+        let computed = transform_from_2d(parent.affine_inverse() * global, 0.0);
+        assert_transforms_eq(computed, transform_from_2d(local, 0.0));
+    }
+
+    #[test]
+    fn local_transform_relative_to_bevy_parent() {
+        let parent = GlobalTransform::from(
+            Transform::from_xyz(4.0, -1.0, 2.0)
+                .with_rotation(Quat::from_rotation_y(FRAC_PI_4))
+                .with_scale(Vec3::splat(2.0)),
+        );
+        let global = Transform::from_xyz(1.0, 2.0, 3.0).with_rotation(Quat::from_rotation_x(0.5));
+
+        // propagation applies the parent to the local transform exactly once
+        let local = local_from_global(global, Some(&parent));
+        assert_transforms_eq(parent.mul_transform(local).compute_transform(), global);
+        assert_transforms_eq(global_from_local(&local, Some(&parent)), global);
+        assert_transforms_eq(local_from_global(global, None), global);
+    }
+}
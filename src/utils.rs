@@ -14,31 +14,66 @@ use std::{
 #[derive(Resource)]
 pub struct GodotVisualFrame;
 
-/// Bevy Resource that is available when the app is updated through `_physics_process` callback
+/// Bevy Resource that is available when the app is updated through `_physics_process` callback,
+/// i.e. while the `FixedMain` schedules run
 #[derive(Resource)]
 pub struct GodotPhysicsFrame;
 
 /// Adds `as_physics_system` that schedules a system only for the physics frame
+///
+/// The physics frame only lasts while the `FixedMain` schedules run, a physics system added to
+/// another schedule never runs and logs an error the first time it is skipped.
+#[deprecated(
+    note = "`_physics_process` only runs the `FixedMain` schedules, add the system to `FixedUpdate` instead"
+)]
 pub trait AsPhysicsSystem<Params> {
     #[allow(clippy::wrong_self_convention)]
     fn as_physics_system(self) -> ScheduleConfigs<ScheduleSystem>;
 }
 
+#[allow(deprecated)]
 impl<Params, T: IntoSystem<(), (), Params>> AsPhysicsSystem<Params> for T {
     fn as_physics_system(self) -> ScheduleConfigs<ScheduleSystem> {
-        self.run_if(resource_exists::<GodotPhysicsFrame>)
+        let system = IntoSystem::into_system(self);
+        let name = system.name();
+        system.run_if(
+            move |frame: Option<Res<GodotPhysicsFrame>>, mut reported: Local<bool>| {
+                if frame.is_none() && !*reported {
+                    *reported = true;
+                    error!("the physics system {name} runs outside of `FixedMain` and is skipped, add it to `FixedUpdate` instead");
+                }
+                frame.is_some()
+            },
+        )
     }
 }
 
 /// Adds `as_visual_system` that schedules a system only for the frame
+///
+/// The visual frame doesn't include the `FixedMain` schedules, a visual system added to them never
+/// runs and logs an error the first time it is skipped.
+#[deprecated(
+    note = "`_process` runs the `Main` schedule without `FixedMain`, systems in `Update` are visual systems"
+)]
 pub trait AsVisualSystem<Params> {
     #[allow(clippy::wrong_self_convention)]
     fn as_visual_system(self) -> ScheduleConfigs<ScheduleSystem>;
 }
 
+#[allow(deprecated)]
 impl<Params, T: IntoSystem<(), (), Params>> AsVisualSystem<Params> for T {
     fn as_visual_system(self) -> ScheduleConfigs<ScheduleSystem> {
-        self.run_if(resource_exists::<GodotVisualFrame>)
+        let system = IntoSystem::into_system(self);
+        let name = system.name();
+        system.run_if(
+            move |frame: Option<Res<GodotVisualFrame>>, mut reported: Local<bool>| {
+                if frame.is_none() && !*reported {
+                    *reported = true;
+                    error!("the visual system {name} runs in `FixedMain` and is skipped, add it to `Update` instead");
+                }
+                frame.is_some()
+            },
+        )
     }
 }
 
@@ -46,6 +81,9 @@ impl<Params, T: IntoSystem<(), (), Params>> AsVisualSystem<Params> for T {
 ///
 /// Not every system runs on a Bevy update and Bevy can be updated multiple
 /// during a "frame".
+///
+/// Bevy's `Time` already follows Godot's frame and physics deltas, so this is only needed to
+/// measure wall time between runs of a system.
 #[derive(SystemParam)]
 pub struct SystemDeltaTimer<'w, 's> {
     last_time: Local<'s, Option<Instant>>,
@@ -0,0 +1,844 @@
+use bevy::{
+    math::{IVec2, IVec3, IVec4, Quat, Vec2, Vec3, Vec3A, Vec4},
+    reflect::{
+        DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicSet, DynamicStruct,
+        DynamicTuple, DynamicTupleStruct, DynamicVariant, FromReflect, PartialReflect, ReflectRef,
+        TypeInfo, Typed, VariantInfo, VariantType as EnumVariantType,
+    },
+};
+use godot::{
+    builtin::{
+        Dictionary, GString, Quaternion, Variant, VariantArray, VariantType, Vector2, Vector2i,
+        Vector3, Vector3i, Vector4, Vector4i,
+    },
+    meta::ToGodot,
+};
+use std::{any::TypeId, fmt, slice};
+
+/// Error converting between a reflected value and a [`Variant`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariantConversionError {
+    /// The type has no `Variant` representation, e.g. an opaque type other than the primitives,
+    /// `String` and the glam vectors. Holds the type path.
+    Unsupported(String),
+    /// The `Variant` doesn't have the expected type.
+    Mismatch {
+        expected: String,
+        found: VariantType,
+    },
+    /// The value converted from a `Variant` is missing fields of the type, or names a variant the
+    /// enum doesn't have. Holds the type path.
+    Incomplete(String),
+}
+
+impl fmt::Display for VariantConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(type_path) => {
+                write!(f, "{type_path} can't be converted to or from a Variant")
+            }
+            Self::Mismatch { expected, found } => {
+                write!(f, "expected a Variant of {expected}, found {found:?}")
+            }
+            Self::Incomplete(type_path) => {
+                write!(f, "the Variant doesn't describe a complete {type_path}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VariantConversionError {}
+
+/// Converts a reflected value to a [`Variant`].
+///
+/// Structs and maps become `Dictionary`s, lists, arrays, sets, tuples and tuple structs become
+/// `Array`s, and newtypes the `Variant` of their field. Unit enum variants become the variant's
+/// name, other variants a `Dictionary` with the name as its only key, and `Option`s their value or
+/// `null`. Glam vectors and quaternions become their Godot counterparts.
+pub fn reflect_to_variant(value: &dyn PartialReflect) -> Result<Variant, VariantConversionError> {
+    reflect_to_value(value).map(|value| value.to_variant())
+}
+
+/// Converts a [`Variant`] to a `T`, the inverse of [`reflect_to_variant`].
+pub fn reflect_from_variant<T: FromReflect + Typed>(
+    variant: &Variant,
+) -> Result<T, VariantConversionError> {
+    reflect_from_value(&Value::from_variant(variant))
+}
+
+/// Applies a [`Variant`] to a reflected value, e.g. a component. Fields missing from a
+/// `Dictionary` are left unchanged.
+pub fn apply_variant(
+    target: &mut dyn PartialReflect,
+    variant: &Variant,
+) -> Result<(), VariantConversionError> {
+    apply_value(target, &Value::from_variant(variant))
+}
+
+/// Converts a [`Variant`] to a dynamic value of the type described by `type_info`, which can be
+/// applied to a value of that type or turned into one with `FromReflect`.
+///
+/// Values of the primitive types, `String` and the glam types are concrete instead.
+pub fn variant_to_dynamic(
+    variant: &Variant,
+    type_info: &'static TypeInfo,
+) -> Result<Box<dyn PartialReflect>, VariantConversionError> {
+    value_to_dynamic(&Value::from_variant(variant), type_info)
+}
+
+/// The `Variant`s the conversions read and write, as plain Rust values. Walking the reflected
+/// types happens on these, so it doesn't need a running Godot engine, and only the conversion to
+/// and from `Variant` does.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Vector2(Vec2),
+    Vector2i(IVec2),
+    Vector3(Vec3),
+    Vector3i(IVec3),
+    Vector4(Vec4),
+    Vector4i(IVec4),
+    Quaternion(Quat),
+    Array(Vec<Value>),
+    /// The entries in the `Dictionary`'s order
+    Dictionary(Vec<(Value, Value)>),
+    /// A `Variant` no reflected type converts from, e.g. an `Object` or a typed `Array`
+    Other(VariantType),
+}
+
+impl Value {
+    fn from_variant(variant: &Variant) -> Self {
+        match variant.get_type() {
+            VariantType::NIL => Self::Nil,
+            VariantType::BOOL => Self::Bool(variant.to()),
+            VariantType::INT => Self::Int(variant.to()),
+            VariantType::FLOAT => Self::Float(variant.to()),
+            VariantType::STRING => Self::String(variant.to::<GString>().to_string()),
+            VariantType::VECTOR2 => {
+                let value = variant.to::<Vector2>();
+                Self::Vector2(Vec2::new(value.x, value.y))
+            }
+            VariantType::VECTOR2I => {
+                let value = variant.to::<Vector2i>();
+                Self::Vector2i(IVec2::new(value.x, value.y))
+            }
+            VariantType::VECTOR3 => {
+                let value = variant.to::<Vector3>();
+                Self::Vector3(Vec3::new(value.x, value.y, value.z))
+            }
+            VariantType::VECTOR3I => {
+                let value = variant.to::<Vector3i>();
+                Self::Vector3i(IVec3::new(value.x, value.y, value.z))
+            }
+            VariantType::VECTOR4 => {
+                let value = variant.to::<Vector4>();
+                Self::Vector4(Vec4::new(value.x, value.y, value.z, value.w))
+            }
+            VariantType::VECTOR4I => {
+                let value = variant.to::<Vector4i>();
+                Self::Vector4i(IVec4::new(value.x, value.y, value.z, value.w))
+            }
+            VariantType::QUATERNION => {
+                let value = variant.to::<Quaternion>();
+                Self::Quaternion(Quat::from_xyzw(value.x, value.y, value.z, value.w))
+            }
+            VariantType::ARRAY => match variant.try_to::<VariantArray>() {
+                Ok(array) => Self::Array(
+                    array
+                        .iter_shared()
+                        .map(|item| Self::from_variant(&item))
+                        .collect(),
+                ),
+                Err(_) => Self::Other(VariantType::ARRAY),
+            },
+            VariantType::DICTIONARY => Self::Dictionary(
+                variant
+                    .to::<Dictionary>()
+                    .iter_shared()
+                    .map(|(key, value)| (Self::from_variant(&key), Self::from_variant(&value)))
+                    .collect(),
+            ),
+            variant_type => Self::Other(variant_type),
+        }
+    }
+
+    fn to_variant(&self) -> Variant {
+        match self {
+            Self::Nil => Variant::nil(),
+            Self::Bool(value) => value.to_variant(),
+            Self::Int(value) => value.to_variant(),
+            Self::Float(value) => value.to_variant(),
+            Self::String(value) => value.to_variant(),
+            Self::Vector2(value) => Vector2::new(value.x, value.y).to_variant(),
+            Self::Vector2i(value) => Vector2i::new(value.x, value.y).to_variant(),
+            Self::Vector3(value) => Vector3::new(value.x, value.y, value.z).to_variant(),
+            Self::Vector3i(value) => Vector3i::new(value.x, value.y, value.z).to_variant(),
+            Self::Vector4(value) => Vector4::new(value.x, value.y, value.z, value.w).to_variant(),
+            Self::Vector4i(value) => Vector4i::new(value.x, value.y, value.z, value.w).to_variant(),
+            Self::Quaternion(value) => {
+                Quaternion::new(value.x, value.y, value.z, value.w).to_variant()
+            }
+            Self::Array(items) => items
+                .iter()
+                .map(Self::to_variant)
+                .collect::<VariantArray>()
+                .to_variant(),
+            Self::Dictionary(entries) => {
+                let mut dictionary = Dictionary::new();
+                for (key, value) in entries {
+                    dictionary.set(key.to_variant(), value.to_variant());
+                }
+                dictionary.to_variant()
+            }
+            Self::Other(_) => unreachable!("only read from a Variant"),
+        }
+    }
+
+    fn variant_type(&self) -> VariantType {
+        match self {
+            Self::Nil => VariantType::NIL,
+            Self::Bool(_) => VariantType::BOOL,
+            Self::Int(_) => VariantType::INT,
+            Self::Float(_) => VariantType::FLOAT,
+            Self::String(_) => VariantType::STRING,
+            Self::Vector2(_) => VariantType::VECTOR2,
+            Self::Vector2i(_) => VariantType::VECTOR2I,
+            Self::Vector3(_) => VariantType::VECTOR3,
+            Self::Vector3i(_) => VariantType::VECTOR3I,
+            Self::Vector4(_) => VariantType::VECTOR4,
+            Self::Vector4i(_) => VariantType::VECTOR4I,
+            Self::Quaternion(_) => VariantType::QUATERNION,
+            Self::Array(_) => VariantType::ARRAY,
+            Self::Dictionary(_) => VariantType::DICTIONARY,
+            Self::Other(variant_type) => *variant_type,
+        }
+    }
+
+    /// Also accepts floats without a fractional part
+    fn as_int(&self) -> Option<i64> {
+        match *self {
+            Self::Int(value) => Some(value),
+            Self::Float(value) if value.fract() == 0.0 => Some(value as i64),
+            _ => None,
+        }
+    }
+
+    /// Also accepts integers
+    fn as_float(&self) -> Option<f64> {
+        match *self {
+            Self::Int(value) => Some(value as f64),
+            Self::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+fn reflect_to_value(value: &dyn PartialReflect) -> Result<Value, VariantConversionError> {
+    if let Some(value) = leaf_to_value(value)? {
+        return Ok(value);
+    }
+
+    Ok(match value.reflect_ref() {
+        ReflectRef::Struct(value) => Value::Dictionary(
+            value
+                .iter_fields()
+                .enumerate()
+                .map(|(index, field)| {
+                    let name = value.name_at(index).unwrap().to_string();
+                    Ok((Value::String(name), reflect_to_value(field)?))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        ReflectRef::TupleStruct(value) if value.field_len() == 1 => {
+            reflect_to_value(value.field(0).unwrap())?
+        }
+        ReflectRef::TupleStruct(value) => fields_to_array(value.iter_fields())?,
+        ReflectRef::Tuple(value) => fields_to_array(value.iter_fields())?,
+        ReflectRef::List(value) => fields_to_array(value.iter())?,
+        ReflectRef::Array(value) => fields_to_array(value.iter())?,
+        ReflectRef::Set(value) => fields_to_array(value.iter())?,
+        ReflectRef::Map(value) => Value::Dictionary(
+            value
+                .iter()
+                .map(|(key, value)| Ok((reflect_to_value(key)?, reflect_to_value(value)?)))
+                .collect::<Result<_, _>>()?,
+        ),
+        ReflectRef::Enum(value) => {
+            let payload = match value.variant_type() {
+                EnumVariantType::Unit => None,
+                EnumVariantType::Tuple if value.field_len() == 1 => {
+                    Some(reflect_to_value(value.field_at(0).unwrap())?)
+                }
+                EnumVariantType::Tuple => Some(fields_to_array(
+                    value.iter_fields().map(|field| field.value()),
+                )?),
+                EnumVariantType::Struct => Some(Value::Dictionary(
+                    value
+                        .iter_fields()
+                        .map(|field| {
+                            let name = field.name().unwrap().to_string();
+                            Ok((Value::String(name), reflect_to_value(field.value())?))
+                        })
+                        .collect::<Result<_, _>>()?,
+                )),
+            };
+
+            let name = Value::String(value.variant_name().to_string());
+            match payload {
+                _ if is_option(value.reflect_type_path()) => payload.unwrap_or(Value::Nil),
+                None => name,
+                Some(payload) => Value::Dictionary(vec![(name, payload)]),
+            }
+        }
+        _ => return Err(unsupported(value.reflect_type_path())),
+    })
+}
+
+fn reflect_from_value<T: FromReflect + Typed>(value: &Value) -> Result<T, VariantConversionError> {
+    let value = value_to_dynamic(value, T::type_info())?;
+    T::from_reflect(value.as_ref())
+        .ok_or_else(|| VariantConversionError::Incomplete(T::type_path().to_string()))
+}
+
+fn apply_value(
+    target: &mut dyn PartialReflect,
+    value: &Value,
+) -> Result<(), VariantConversionError> {
+    let Some(type_info) = target.get_represented_type_info() else {
+        return Err(unsupported(target.reflect_type_path()));
+    };
+
+    let value = value_to_dynamic(value, type_info)?;
+    target
+        .try_apply(value.as_ref())
+        .map_err(|_| VariantConversionError::Incomplete(type_info.type_path().to_string()))
+}
+
+fn value_to_dynamic(
+    value: &Value,
+    type_info: &'static TypeInfo,
+) -> Result<Box<dyn PartialReflect>, VariantConversionError> {
+    if let Some(value) = leaf_from_value(value, type_info.type_id())? {
+        return Ok(value);
+    }
+
+    match type_info {
+        TypeInfo::Struct(info) => {
+            let entries = expect_dictionary(value)?;
+            let mut dynamic = DynamicStruct::default();
+            dynamic.set_represented_type(Some(type_info));
+            for field in info.iter() {
+                let Some(field_value) = get_entry(entries, field.name()) else {
+                    continue;
+                };
+                dynamic.insert_boxed(
+                    field.name(),
+                    field_from_value(field_value, field.type_info(), field.type_path())?,
+                );
+            }
+            Ok(Box::new(dynamic))
+        }
+        TypeInfo::TupleStruct(info) => {
+            let fields = if info.field_len() == 1 {
+                slice::from_ref(value)
+            } else {
+                expect_array(value)?
+            };
+            let mut dynamic = DynamicTupleStruct::default();
+            dynamic.set_represented_type(Some(type_info));
+            for (field, field_value) in info.iter().zip(fields) {
+                dynamic.insert_boxed(field_from_value(
+                    field_value,
+                    field.type_info(),
+                    field.type_path(),
+                )?);
+            }
+            Ok(Box::new(dynamic))
+        }
+        TypeInfo::Tuple(info) => {
+            let mut dynamic = DynamicTuple::default();
+            dynamic.set_represented_type(Some(type_info));
+            for (field, field_value) in info.iter().zip(expect_array(value)?) {
+                dynamic.insert_boxed(field_from_value(
+                    field_value,
+                    field.type_info(),
+                    field.type_path(),
+                )?);
+            }
+            Ok(Box::new(dynamic))
+        }
+        TypeInfo::List(info) => {
+            let mut dynamic = expect_array(value)?
+                .iter()
+                .map(|item| field_from_value(item, info.item_info(), info.item_ty().path()))
+                .collect::<Result<DynamicList, _>>()?;
+            dynamic.set_represented_type(Some(type_info));
+            Ok(Box::new(dynamic))
+        }
+        TypeInfo::Array(info) => {
+            let mut dynamic = expect_array(value)?
+                .iter()
+                .map(|item| field_from_value(item, info.item_info(), info.item_ty().path()))
+                .collect::<Result<DynamicArray, _>>()?;
+            dynamic.set_represented_type(Some(type_info));
+            Ok(Box::new(dynamic))
+        }
+        // `SetInfo` has no type info for its values, so only sets of the types with a direct
+        // `Variant` counterpart are supported
+        TypeInfo::Set(info) => {
+            let mut dynamic = expect_array(value)?
+                .iter()
+                .map(|item| {
+                    leaf_from_value(item, info.value_ty().id())?
+                        .ok_or_else(|| unsupported(info.value_ty().path()))
+                })
+                .collect::<Result<DynamicSet, _>>()?;
+            dynamic.set_represented_type(Some(type_info));
+            Ok(Box::new(dynamic))
+        }
+        TypeInfo::Map(info) => {
+            let mut dynamic = expect_dictionary(value)?
+                .iter()
+                .map(|(key, value)| {
+                    Ok((
+                        field_from_value(key, info.key_info(), info.key_ty().path())?,
+                        field_from_value(value, info.value_info(), info.value_ty().path())?,
+                    ))
+                })
+                .collect::<Result<DynamicMap, _>>()?;
+            dynamic.set_represented_type(Some(type_info));
+            Ok(Box::new(dynamic))
+        }
+        TypeInfo::Enum(info) => {
+            let incomplete = || VariantConversionError::Incomplete(info.type_path().to_string());
+            let (name, payload) = if is_option(info.type_path()) {
+                match value {
+                    Value::Nil => ("None", None),
+                    value => ("Some", Some(value)),
+                }
+            } else {
+                match value {
+                    Value::String(name) => (name.as_str(), None),
+                    Value::Dictionary(entries) => match entries.as_slice() {
+                        [(Value::String(name), payload)] => (name.as_str(), Some(payload)),
+                        _ => return Err(incomplete()),
+                    },
+                    value => return Err(mismatch("String or Dictionary", value)),
+                }
+            };
+
+            let variant = match (info.variant(name).ok_or_else(incomplete)?, payload) {
+                (VariantInfo::Unit(_), _) => DynamicVariant::Unit,
+                (VariantInfo::Tuple(variant_info), Some(payload)) => {
+                    let fields = if variant_info.field_len() == 1 {
+                        slice::from_ref(payload)
+                    } else {
+                        expect_array(payload)?
+                    };
+                    let mut tuple = DynamicTuple::default();
+                    for (field, field_value) in variant_info.iter().zip(fields) {
+                        tuple.insert_boxed(field_from_value(
+                            field_value,
+                            field.type_info(),
+                            field.type_path(),
+                        )?);
+                    }
+                    DynamicVariant::Tuple(tuple)
+                }
+                (VariantInfo::Struct(variant_info), Some(payload)) => {
+                    let entries = expect_dictionary(payload)?;
+                    let mut dynamic = DynamicStruct::default();
+                    for field in variant_info.iter() {
+                        let Some(field_value) = get_entry(entries, field.name()) else {
+                            continue;
+                        };
+                        dynamic.insert_boxed(
+                            field.name(),
+                            field_from_value(field_value, field.type_info(), field.type_path())?,
+                        );
+                    }
+                    DynamicVariant::Struct(dynamic)
+                }
+                (_, None) => return Err(incomplete()),
+            };
+
+            let mut dynamic = DynamicEnum::new(name, variant);
+            dynamic.set_represented_type(Some(type_info));
+            Ok(Box::new(dynamic))
+        }
+        _ => Err(unsupported(type_info.type_path())),
+    }
+}
+
+/// Converts a field, whose type info is missing if its type isn't `Typed`
+fn field_from_value(
+    value: &Value,
+    type_info: Option<&'static TypeInfo>,
+    type_path: &str,
+) -> Result<Box<dyn PartialReflect>, VariantConversionError> {
+    value_to_dynamic(value, type_info.ok_or_else(|| unsupported(type_path))?)
+}
+
+fn fields_to_array<'a>(
+    fields: impl Iterator<Item = &'a dyn PartialReflect>,
+) -> Result<Value, VariantConversionError> {
+    fields
+        .map(reflect_to_value)
+        .collect::<Result<_, _>>()
+        .map(Value::Array)
+}
+
+/// Converts the types with a direct `Variant` counterpart, returns `None` for other types
+fn leaf_to_value(value: &dyn PartialReflect) -> Result<Option<Value>, VariantConversionError> {
+    macro_rules! convert {
+        ($($ty:ty => $convert:expr),* $(,)?) => {
+            $(if let Some(value) = value.try_downcast_ref::<$ty>() {
+                #[allow(clippy::redundant_closure_call)]
+                return Ok(Some(($convert)(value)));
+            })*
+        };
+    }
+
+    convert!(
+        bool => |value: &bool| Value::Bool(*value),
+        i8 => |value: &i8| Value::Int((*value).into()),
+        i16 => |value: &i16| Value::Int((*value).into()),
+        i32 => |value: &i32| Value::Int((*value).into()),
+        i64 => |value: &i64| Value::Int(*value),
+        u8 => |value: &u8| Value::Int((*value).into()),
+        u16 => |value: &u16| Value::Int((*value).into()),
+        u32 => |value: &u32| Value::Int((*value).into()),
+        f32 => |value: &f32| Value::Float((*value).into()),
+        f64 => |value: &f64| Value::Float(*value),
+        String => |value: &String| Value::String(value.clone()),
+        Vec2 => |value: &Vec2| Value::Vector2(*value),
+        Vec3 => |value: &Vec3| Value::Vector3(*value),
+        Vec3A => |value: &Vec3A| Value::Vector3((*value).into()),
+        Vec4 => |value: &Vec4| Value::Vector4(*value),
+        IVec2 => |value: &IVec2| Value::Vector2i(*value),
+        IVec3 => |value: &IVec3| Value::Vector3i(*value),
+        IVec4 => |value: &IVec4| Value::Vector4i(*value),
+        Quat => |value: &Quat| Value::Quaternion(*value),
+    );
+
+    // Godot's integers are 64 bits
+    let integer = value
+        .try_downcast_ref::<u64>()
+        .map(|value| i64::try_from(*value).ok())
+        .or_else(|| {
+            value
+                .try_downcast_ref::<usize>()
+                .map(|value| i64::try_from(*value).ok())
+        })
+        .or_else(|| {
+            value
+                .try_downcast_ref::<isize>()
+                .map(|value| i64::try_from(*value).ok())
+        });
+
+    match integer {
+        Some(Some(integer)) => Ok(Some(Value::Int(integer))),
+        Some(None) => Err(unsupported(value.reflect_type_path())),
+        None => Ok(None),
+    }
+}
+
+/// Converts the types with a direct `Variant` counterpart, returns `None` for other types
+fn leaf_from_value(
+    value: &Value,
+    type_id: TypeId,
+) -> Result<Option<Box<dyn PartialReflect>>, VariantConversionError> {
+    macro_rules! convert {
+        ($($ty:ty => $convert:expr),* $(,)?) => {
+            $(if type_id == TypeId::of::<$ty>() {
+                #[allow(clippy::redundant_closure_call)]
+                let converted: Option<$ty> = ($convert)(value);
+                return converted
+                    .map(|converted| Some(Box::new(converted) as Box<dyn PartialReflect>))
+                    .ok_or_else(|| mismatch(stringify!($ty), value));
+            })*
+        };
+    }
+
+    convert!(
+        bool => |value: &Value| match value { Value::Bool(value) => Some(*value), _ => None },
+        i8 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        i16 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        i32 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        i64 => |value: &Value| value.as_int(),
+        isize => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        u8 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        u16 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        u32 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        u64 => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        usize => |value: &Value| value.as_int().and_then(|value| value.try_into().ok()),
+        f32 => |value: &Value| value.as_float().map(|value| value as f32),
+        f64 => |value: &Value| value.as_float(),
+        String => |value: &Value| match value { Value::String(value) => Some(value.clone()), _ => None },
+        Vec2 => |value: &Value| match value { Value::Vector2(value) => Some(*value), _ => None },
+        Vec3 => |value: &Value| match value { Value::Vector3(value) => Some(*value), _ => None },
+        Vec3A => |value: &Value| match value { Value::Vector3(value) => Some((*value).into()), _ => None },
+        Vec4 => |value: &Value| match value { Value::Vector4(value) => Some(*value), _ => None },
+        IVec2 => |value: &Value| match value { Value::Vector2i(value) => Some(*value), _ => None },
+        IVec3 => |value: &Value| match value { Value::Vector3i(value) => Some(*value), _ => None },
+        IVec4 => |value: &Value| match value { Value::Vector4i(value) => Some(*value), _ => None },
+        Quat => |value: &Value| match value { Value::Quaternion(value) => Some(*value), _ => None },
+    );
+
+    Ok(None)
+}
+
+fn expect_array(value: &Value) -> Result<&[Value], VariantConversionError> {
+    match value {
+        Value::Array(items) => Ok(items),
+        value => Err(mismatch("Array", value)),
+    }
+}
+
+fn expect_dictionary(value: &Value) -> Result<&[(Value, Value)], VariantConversionError> {
+    match value {
+        Value::Dictionary(entries) => Ok(entries),
+        value => Err(mismatch("Dictionary", value)),
+    }
+}
+
+/// The value of the `Dictionary` entry whose key is the `String` `key`
+fn get_entry<'a>(entries: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+    entries
+        .iter()
+        .find(|(entry_key, _)| matches!(entry_key, Value::String(entry_key) if entry_key == key))
+        .map(|(_, value)| value)
+}
+
+fn is_option(type_path: &str) -> bool {
+    type_path.starts_with("core::option::Option<")
+}
+
+fn mismatch(expected: &str, found: &Value) -> VariantConversionError {
+    VariantConversionError::Mismatch {
+        expected: expected.to_string(),
+        found: found.variant_type(),
+    }
+}
+
+fn unsupported(type_path: &str) -> VariantConversionError {
+    VariantConversionError::Unsupported(type_path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{
+        platform::collections::{HashMap, HashSet},
+        prelude::{Reflect, ReflectDefault},
+    };
+
+    #[derive(Reflect, Debug, Clone, PartialEq, Default)]
+    #[reflect(Default)]
+    struct Stats {
+        health: i32,
+        name: String,
+        speed: f32,
+        position: Vec2,
+    }
+
+    #[derive(Reflect, Debug, Clone, PartialEq)]
+    struct Required {
+        health: i32,
+        name: String,
+    }
+
+    #[derive(Reflect, Debug, Clone, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f32),
+        Segment(Vec2, Vec2),
+        Rect { width: f32, height: f32 },
+    }
+
+    #[derive(Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+    enum Team {
+        Red,
+        Blue,
+    }
+
+    fn stats() -> Stats {
+        Stats {
+            health: 80,
+            name: "knight".to_string(),
+            speed: 2.5,
+            position: Vec2::new(1.0, -3.0),
+        }
+    }
+
+    fn string(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    fn dictionary<const N: usize>(entries: [(&str, Value); N]) -> Value {
+        Value::Dictionary(
+            entries
+                .into_iter()
+                .map(|(key, value)| (string(key), value))
+                .collect(),
+        )
+    }
+
+    fn round_trip<T: FromReflect + Typed>(value: &T) -> T {
+        let value = reflect_to_value(value).unwrap();
+        reflect_from_value(&value).unwrap()
+    }
+
+    #[test]
+    fn struct_round_trip() {
+        let value = reflect_to_value(&stats()).unwrap();
+
+        assert_eq!(
+            value,
+            dictionary([
+                ("health", Value::Int(80)),
+                ("name", string("knight")),
+                ("speed", Value::Float(2.5)),
+                ("position", Value::Vector2(Vec2::new(1.0, -3.0))),
+            ])
+        );
+        assert_eq!(reflect_from_value::<Stats>(&value).unwrap(), stats());
+    }
+
+    #[test]
+    fn enum_round_trip() {
+        for shape in [
+            Shape::Empty,
+            Shape::Circle(2.0),
+            Shape::Segment(Vec2::ZERO, Vec2::ONE),
+            Shape::Rect {
+                width: 3.0,
+                height: 4.0,
+            },
+        ] {
+            assert_eq!(round_trip(&shape), shape);
+        }
+
+        assert_eq!(reflect_to_value(&Shape::Empty).unwrap(), string("Empty"));
+        assert_eq!(
+            reflect_to_value(&Shape::Circle(2.0)).unwrap(),
+            dictionary([("Circle", Value::Float(2.0))])
+        );
+    }
+
+    #[test]
+    fn list_round_trip() {
+        let list = vec![Shape::Circle(1.0), Shape::Empty];
+        assert_eq!(round_trip(&list), list);
+
+        let array = [IVec2::new(1, 2), IVec2::new(-3, 4)];
+        assert_eq!(round_trip(&array), array);
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let map: HashMap<String, Stats> = [("player".to_string(), stats())].into_iter().collect();
+        assert_eq!(round_trip(&map), map);
+    }
+
+    #[test]
+    fn set_round_trip() {
+        let set: HashSet<String> = ["red".to_string(), "blue".to_string()]
+            .into_iter()
+            .collect();
+        assert_eq!(round_trip(&set), set);
+
+        // the type info of the values isn't available for anything but the leaf types
+        let teams = reflect_to_value(&vec![Team::Red, Team::Blue]).unwrap();
+        assert!(matches!(
+            reflect_from_value::<HashSet<Team>>(&teams),
+            Err(VariantConversionError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn option_round_trip() {
+        assert_eq!(reflect_to_value(&Some(3_u32)).unwrap(), Value::Int(3));
+        assert_eq!(reflect_to_value(&None::<u32>).unwrap(), Value::Nil);
+        assert_eq!(round_trip(&Some(stats())), Some(stats()));
+        assert_eq!(round_trip(&None::<Stats>), None);
+    }
+
+    #[test]
+    fn numbers_convert_between_int_and_float() {
+        assert_eq!(reflect_from_value::<f32>(&Value::Int(3)).unwrap(), 3.0);
+        assert_eq!(reflect_from_value::<u8>(&Value::Float(3.0)).unwrap(), 3);
+        assert!(reflect_from_value::<u8>(&Value::Float(3.5)).is_err());
+        assert!(reflect_from_value::<u8>(&Value::Int(300)).is_err());
+    }
+
+    #[test]
+    fn missing_fields() {
+        let value = dictionary([("health", Value::Int(10))]);
+
+        // taken from `Default` with `#[reflect(Default)]`
+        assert_eq!(
+            reflect_from_value::<Stats>(&value).unwrap(),
+            Stats {
+                health: 10,
+                ..Default::default()
+            }
+        );
+        assert!(matches!(
+            reflect_from_value::<Required>(&value),
+            Err(VariantConversionError::Incomplete(_))
+        ));
+
+        // left unchanged when applied
+        let mut target = stats();
+        apply_value(&mut target, &value).unwrap();
+        assert_eq!(
+            target,
+            Stats {
+                health: 10,
+                ..stats()
+            }
+        );
+    }
+
+    #[test]
+    fn extra_fields() {
+        let value = dictionary([
+            ("health", Value::Int(10)),
+            ("name", string("archer")),
+            ("mana", Value::Int(5)),
+        ]);
+
+        assert_eq!(
+            reflect_from_value::<Required>(&value).unwrap(),
+            Required {
+                health: 10,
+                name: "archer".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn mismatched_type() {
+        let value = dictionary([("health", string("full"))]);
+
+        assert!(matches!(
+            reflect_from_value::<Stats>(&value),
+            Err(VariantConversionError::Mismatch {
+                found: VariantType::STRING,
+                ..
+            })
+        ));
+        assert!(matches!(
+            reflect_from_value::<Stats>(&Value::Other(VariantType::OBJECT)),
+            Err(VariantConversionError::Mismatch {
+                found: VariantType::OBJECT,
+                ..
+            })
+        ));
+    }
+}